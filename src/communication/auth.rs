@@ -0,0 +1,281 @@
+use crate::communication::message::{Message, MessageContent, MessageType};
+use crate::robot::Direction;
+use crate::util::Coord;
+
+// Key shared by every robot of a team, signs messages with SipHash-2-4
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MacKey {
+  pub k0: u64,
+  pub k1: u64,
+}
+
+impl MacKey {
+  pub fn sign(&self, message: &Message) -> u64 {
+    self.siphash(&Self::authenticated_bytes(message))
+  }
+
+  pub fn verify(&self, message: &Message) -> bool {
+    self.sign(message) == message.mac
+  }
+
+  // Everything the sender sets, the turns are stamped by the message board on the way
+  fn authenticated_bytes(message: &Message) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(message.sender_id as u32).to_le_bytes());
+    bytes.push(Self::type_byte(message.msg_type));
+    bytes.extend_from_slice(&message.id.to_le_bytes());
+    bytes.extend_from_slice(&message.epoch.to_le_bytes());
+    bytes.extend_from_slice(&message.ballot.to_le_bytes());
+    match message.ttl {
+      Some(ttl) => {
+        bytes.push(1);
+        bytes.extend_from_slice(&ttl.to_le_bytes());
+      },
+      None => bytes.push(0),
+    }
+    match message.message_content {
+      MessageContent::Coord(coord, gold_amount) => {
+        bytes.push(0);
+        Self::push_coord(&mut bytes, coord);
+        Self::push_amount(&mut bytes, gold_amount);
+      },
+      MessageContent::Gold(coord, bars, value) => {
+        bytes.push(5);
+        Self::push_coord(&mut bytes, Some(coord));
+        bytes.push(bars);
+        bytes.extend_from_slice(&value.to_le_bytes());
+      },
+      MessageContent::Group(members) => {
+        bytes.push(1);
+        bytes.push(members.iter().flatten().count() as u8);
+        for id in members.iter().flatten() {
+          bytes.extend_from_slice(&(*id as u32).to_le_bytes());
+        }
+      },
+      MessageContent::Direction(direction) => {
+        bytes.push(2);
+        bytes.push(Self::direction_byte(direction));
+      },
+      MessageContent::TurnReq(direction, coord) => {
+        bytes.push(3);
+        bytes.push(Self::direction_byte(direction));
+        Self::push_coord(&mut bytes, Some(coord));
+      },
+      MessageContent::Observations(observations, turn) => {
+        bytes.push(4);
+        bytes.push(observations.iter().flatten().count() as u8);
+        for (coord, gold, red_robots, blue_robots) in observations.iter().flatten() {
+          Self::push_coord(&mut bytes, Some(*coord));
+          match gold {
+            Some((bars, value)) => {
+              bytes.extend_from_slice(&[1, *bars]);
+              bytes.extend_from_slice(&value.to_le_bytes());
+            },
+            None => bytes.push(0),
+          }
+          bytes.push(*red_robots);
+          bytes.push(*blue_robots);
+        }
+        bytes.extend_from_slice(&turn.to_le_bytes());
+      },
+    }
+    bytes
+  }
+
+  fn push_coord(bytes: &mut Vec<u8>, coord: Option<Coord>) {
+    match coord {
+      Some(coord) => {
+        bytes.push(1);
+        bytes.extend_from_slice(&(coord.x as u64).to_le_bytes());
+        bytes.extend_from_slice(&(coord.y as u64).to_le_bytes());
+      },
+      None => bytes.push(0),
+    }
+  }
+
+  fn push_amount(bytes: &mut Vec<u8>, gold_amount: Option<u8>) {
+    match gold_amount {
+      Some(gold_amount) => bytes.extend_from_slice(&[1, gold_amount]),
+      None => bytes.push(0),
+    }
+  }
+
+  fn type_byte(msg_type: MessageType) -> u8 {
+    match msg_type {
+      MessageType::PrepareRequest => 0,
+      MessageType::PrepareResponse => 1,
+      MessageType::AcceptRequest => 2,
+      MessageType::Accepted => 3,
+      MessageType::Confirm => 4,
+      MessageType::Nack => 5,
+      MessageType::Simple => 6,
+      MessageType::Request => 7,
+      MessageType::Ack => 8,
+      MessageType::Done => 9,
+      MessageType::GetOut => 10,
+      MessageType::KnowledgeSync => 11,
+    }
+  }
+
+  fn direction_byte(direction: Direction) -> u8 {
+    match direction {
+      Direction::Left => 0,
+      Direction::Right => 1,
+      Direction::Up => 2,
+      Direction::Down => 3,
+    }
+  }
+}
+
+// SipHash-2-4
+impl MacKey {
+  fn siphash(&self, bytes: &[u8]) -> u64 {
+    let mut v0 = self.k0 ^ 0x736f6d6570736575;
+    let mut v1 = self.k1 ^ 0x646f72616e646f6d;
+    let mut v2 = self.k0 ^ 0x6c7967656e657261;
+    let mut v3 = self.k1 ^ 0x7465646279746573;
+
+    let chunks = bytes.chunks_exact(8);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+      let m = u64::from_le_bytes(chunk.try_into().unwrap());
+      v3 ^= m;
+      for _ in 0..2 {
+        Self::round(&mut v0, &mut v1, &mut v2, &mut v3);
+      }
+      v0 ^= m;
+    }
+
+    let mut last = (bytes.len() as u64 & 0xff) << 56;
+    for (i, byte) in tail.iter().enumerate() {
+      last |= (*byte as u64) << (8 * i);
+    }
+    v3 ^= last;
+    for _ in 0..2 {
+      Self::round(&mut v0, &mut v1, &mut v2, &mut v3);
+    }
+    v0 ^= last;
+
+    v2 ^= 0xff;
+    for _ in 0..4 {
+      Self::round(&mut v0, &mut v1, &mut v2, &mut v3);
+    }
+    v0 ^ v1 ^ v2 ^ v3
+  }
+
+  fn round(v0: &mut u64, v1: &mut u64, v2: &mut u64, v3: &mut u64) {
+    *v0 = v0.wrapping_add(*v1);
+    *v1 = v1.rotate_left(13);
+    *v1 ^= *v0;
+    *v0 = v0.rotate_left(32);
+    *v2 = v2.wrapping_add(*v3);
+    *v3 = v3.rotate_left(16);
+    *v3 ^= *v2;
+    *v0 = v0.wrapping_add(*v3);
+    *v3 = v3.rotate_left(21);
+    *v3 ^= *v0;
+    *v2 = v2.wrapping_add(*v1);
+    *v1 = v1.rotate_left(17);
+    *v1 ^= *v2;
+    *v2 = v2.rotate_left(32);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::communication::message::MAX_OBSERVATIONS;
+
+  // Key 00 01 .. 0f from the SipHash paper
+  const KEY: MacKey = MacKey { k0: 0x0706050403020100, k1: 0x0f0e0d0c0b0a0908 };
+
+  // Reference outputs for the messages 00, 00 01, .. of the given lengths
+  const VECTORS: [(usize, u64); 6] = [
+    (0, 0x726fdb47dd0e0e31),
+    (1, 0x74f839c593dc67fd),
+    (2, 0x0d6c8009d9a94f5a),
+    (7, 0xab0200f58b01d137),
+    (8, 0x93f5f5799a932462),
+    (15, 0xa129ca6149be45e5),
+  ];
+
+  #[test]
+  fn siphash_matches_reference_vectors() {
+    for (length, expected) in VECTORS {
+      let bytes: Vec<u8> = (0..length as u8).collect();
+      assert_eq!(KEY.siphash(&bytes), expected, "message of {} bytes", length);
+    }
+  }
+
+  fn signed() -> Message {
+    let mut message = Message::new('A', MessageType::PrepareResponse, 7, MessageContent::Coord(Some(Coord { x: 3, y: 4 }), Some(2))).with_ballot(9);
+    message.epoch = 2;
+    message.ttl = Some(5);
+    message.mac = KEY.sign(&message);
+    message
+  }
+
+  #[test]
+  fn signed_message_verifies() {
+    assert!(KEY.verify(&signed()));
+    assert!(!MacKey { k0: 1, k1: 2 }.verify(&signed()));
+  }
+
+  #[test]
+  fn board_stamped_turns_are_not_authenticated() {
+    let mut message = signed();
+    message.sent_turn = 10;
+    message.ready_turn = 11;
+    assert!(KEY.verify(&message));
+  }
+
+  #[test]
+  fn tampering_with_any_field_fails_verification() {
+    let tampers: [fn(&mut Message); 8] = [
+      |message| message.sender_id = 'B',
+      |message| message.msg_type = MessageType::Accepted,
+      |message| message.id = 8,
+      |message| message.epoch = 3,
+      |message| message.ttl = None,
+      |message| message.ballot = 10,
+      |message| message.message_content = MessageContent::Coord(Some(Coord { x: 3, y: 5 }), Some(2)),
+      |message| message.message_content = MessageContent::Coord(Some(Coord { x: 3, y: 4 }), Some(3)),
+    ];
+    for (i, tamper) in tampers.iter().enumerate() {
+      let mut message = signed();
+      tamper(&mut message);
+      assert!(!KEY.verify(&message), "tamper {} still verifies", i);
+    }
+  }
+
+  #[test]
+  fn gold_reports_authenticate_bars_and_value() {
+    let mut report = Message::new('A', MessageType::Simple, 65, MessageContent::Gold(Coord { x: 3, y: 4 }, 2, 300));
+    report.mac = KEY.sign(&report);
+    assert!(KEY.verify(&report));
+    for tampered in [MessageContent::Gold(Coord { x: 3, y: 4 }, 3, 300), MessageContent::Gold(Coord { x: 3, y: 4 }, 2, 301)] {
+      let mut forged = report;
+      forged.message_content = tampered;
+      assert!(!KEY.verify(&forged), "{:?} still verifies", tampered);
+    }
+  }
+
+  #[test]
+  fn every_observation_of_a_sync_is_authenticated() {
+    let mut observations = [None; MAX_OBSERVATIONS];
+    observations[0] = Some((Coord { x: 1, y: 1 }, Some((2, 5)), 1, 0));
+    observations[1] = Some((Coord { x: 2, y: 1 }, None, 0, 1));
+    let mut message = Message::new('A', MessageType::KnowledgeSync, 65, MessageContent::Observations(observations, 4));
+    message.mac = KEY.sign(&message);
+    assert!(KEY.verify(&message));
+    for tampered in [(Coord { x: 2, y: 1 }, None, 0, 2), (Coord { x: 2, y: 1 }, Some((1, 1)), 0, 1)] {
+      let mut tampered_observations = observations;
+      tampered_observations[1] = Some(tampered);
+      let mut forged = message;
+      forged.message_content = MessageContent::Observations(tampered_observations, 4);
+      assert!(!KEY.verify(&forged), "{:?} still verifies", tampered);
+    }
+    message.message_content = MessageContent::Observations(observations, 5);
+    assert!(!KEY.verify(&message));
+  }
+}
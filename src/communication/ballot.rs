@@ -0,0 +1,38 @@
+use std::fmt::{Debug, Formatter};
+
+// Ballots are ordered by round first, the proposer id breaks ties between robots in the same round
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Ballot {
+  pub round: u32,
+  pub proposer_id: char,
+}
+
+impl Ballot {
+  pub fn new(round: u32, proposer_id: char) -> Ballot {
+    Self {
+      round,
+      proposer_id,
+    }
+  }
+
+  // Smallest ballot of this proposer that beats the given one
+  pub fn outbid(&self, proposer_id: char) -> Ballot {
+    Ballot::new(self.round + 1, proposer_id)
+  }
+
+  // Packed into the message id, robot ids are ASCII so they fit in the lowest byte
+  pub fn to_id(&self) -> u32 {
+    (self.round << 8) | (self.proposer_id as u32 & 0xFF)
+  }
+
+  pub fn from_id(id: u32) -> Ballot {
+    Ballot::new(id >> 8, (id & 0xFF) as u8 as char)
+  }
+}
+
+// Print functions
+impl Debug for Ballot {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "<{}, {}>", self.round, self.proposer_id)
+  }
+}
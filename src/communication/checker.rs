@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::mem::discriminant;
+use colored::Colorize;
+use crate::communication::ballot::Ballot;
+use crate::communication::message::{Message, MessageContent, MessageType};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ViolationKind {
+  // Two robots of the same cluster decided different values in the same epoch
+  Disagreement,
+  // A value was confirmed without a majority of Accepted for it
+  NoMajority,
+  // An acceptor accepted a ballot lower than one it had already promised
+  BrokenPromise,
+}
+
+pub struct Violation {
+  pub kind: ViolationKind,
+  pub description: String,
+  pub messages: Vec<Message>,
+}
+
+struct Decision {
+  robot_id: char,
+  epoch: u32,
+  cluster: Vec<char>,
+  message: Message,
+}
+
+// Watches the Paxos traffic of one team and reports safety violations
+pub struct SafetyChecker {
+  panic_on_violation: bool,
+  // Leader based consensus confirms without votes
+  requires_majority: bool,
+  // Highest promise seen per (acceptor, epoch)
+  promises: HashMap<(char, u32), Message>,
+  // Epoch of the latest prepare per (proposer, ballot)
+  prepare_epochs: HashMap<(char, u32), u32>,
+  // Accepted votes per (proposer, proposer epoch)
+  accepted: HashMap<(char, u32), Vec<Message>>,
+  confirmed: HashSet<(char, u32, MessageContent)>,
+  decisions: Vec<Decision>,
+  violations: Vec<Violation>,
+}
+
+// Constructor and getters
+impl SafetyChecker {
+  pub fn new(panic_on_violation: bool, requires_majority: bool) -> SafetyChecker {
+    Self {
+      panic_on_violation,
+      requires_majority,
+      promises: HashMap::new(),
+      prepare_epochs: HashMap::new(),
+      accepted: HashMap::new(),
+      confirmed: HashSet::new(),
+      decisions: Vec::new(),
+      violations: Vec::new(),
+    }
+  }
+
+  pub fn get_violations(&self) -> &Vec<Violation> {
+    &self.violations
+  }
+}
+
+// Checking logic
+impl SafetyChecker {
+  pub fn observe(&mut self, receiver_id: char, message: &Message) {
+    match message.msg_type {
+      MessageType::PrepareRequest => {
+        self.prepare_epochs.insert((message.sender_id, message.id), message.epoch);
+      },
+      MessageType::PrepareResponse | MessageType::Nack => {
+        let promise = self.promises.entry((message.sender_id, message.epoch)).or_insert(*message);
+        *promise = if Self::promised_ballot(message) > Self::promised_ballot(promise) { *message } else { *promise };
+      },
+      MessageType::Accepted => {
+        if let Some(promise) = self.promises.get(&(message.sender_id, message.epoch)) && message.id < Self::promised_ballot(promise) {
+          let description = format!("{} accepted {:?} after promising {:?}", message.sender_id, Ballot::from_id(message.id), Ballot::from_id(Self::promised_ballot(promise)));
+          let messages = vec![*promise, *message];
+          self.report(ViolationKind::BrokenPromise, description, messages);
+        }
+        if let Some(&epoch) = self.prepare_epochs.get(&(receiver_id, message.id)) {
+          self.accepted.entry((receiver_id, epoch)).or_default().push(*message);
+        }
+      },
+      MessageType::Confirm if self.requires_majority => self.check_majority(message),
+      _ => {}
+    }
+  }
+
+  pub fn record_decision(&mut self, robot_id: char, epoch: u32, cluster: Vec<char>, message: Message) {
+    let mut conflicts = Vec::new();
+    for decision in self.decisions.iter() {
+      let same_cluster = decision.cluster.contains(&robot_id) && cluster.contains(&decision.robot_id);
+      let same_kind = discriminant(&decision.message.message_content) == discriminant(&message.message_content);
+      if decision.epoch == epoch && same_cluster && same_kind && !Self::same_value(decision.message.message_content, message.message_content) {
+        conflicts.push((decision.robot_id, decision.message));
+      }
+    }
+    for (other_id, other_message) in conflicts {
+      let description = format!("{} decided {:?} but {} decided {:?} in epoch {}", other_id, other_message.message_content, robot_id, message.message_content, epoch);
+      self.report(ViolationKind::Disagreement, description, vec![other_message, message]);
+    }
+    self.decisions.retain(|decision| decision.robot_id != robot_id || decision.epoch != epoch);
+    self.decisions.push(Decision { robot_id, epoch, cluster, message });
+  }
+
+  fn check_majority(&mut self, confirm: &Message) {
+    // A confirm goes to the whole cluster, check it once
+    if !self.confirmed.insert((confirm.sender_id, confirm.epoch, confirm.message_content)) {
+      return;
+    }
+    let cluster_size = match self.decisions.iter().rev().find(|decision| decision.robot_id == confirm.sender_id && decision.epoch == confirm.epoch) {
+      Some(decision) => decision.cluster.len(),
+      None => return,
+    };
+    let votes = self.accepted.get(&(confirm.sender_id, confirm.epoch)).cloned().unwrap_or_default();
+    let mut voters_per_ballot: HashMap<u32, HashSet<char>> = HashMap::new();
+    for vote in votes.iter().filter(|vote| Self::same_value(vote.message_content, confirm.message_content)) {
+      voters_per_ballot.entry(vote.id).or_default().insert(vote.sender_id);
+    }
+    let best = voters_per_ballot.values().map(|voters| voters.len()).max().unwrap_or(0);
+    if best <= cluster_size / 2 {
+      let description = format!("{} confirmed {:?} with {} of {} votes", confirm.sender_id, confirm.message_content, best, cluster_size);
+      let mut messages = votes;
+      messages.push(*confirm);
+      self.report(ViolationKind::NoMajority, description, messages);
+    }
+  }
+
+  // A promise carries the ballot it answers, a nack the ballot it was beaten by
+  fn promised_ballot(message: &Message) -> u32 {
+    match message.msg_type {
+      MessageType::PrepareResponse => message.ballot,
+      _ => message.id,
+    }
+  }
+
+  // Groups are unordered
+  fn same_value(a: MessageContent, b: MessageContent) -> bool {
+    match (a, b) {
+      (MessageContent::Group(_), MessageContent::Group(_)) => {
+        let (mut a_members, mut b_members) = (a.members(), b.members());
+        a_members.sort();
+        b_members.sort();
+        a_members == b_members
+      },
+      _ => a == b,
+    }
+  }
+
+  fn report(&mut self, kind: ViolationKind, description: String, messages: Vec<Message>) {
+    let violation = Violation { kind, description, messages };
+    println!("{} {:?}", "SAFETY VIOLATION".on_red().bold(), violation);
+    if self.panic_on_violation {
+      panic!("Safety violation: {:?}", violation);
+    }
+    self.violations.push(violation);
+  }
+}
+
+// Print functions
+impl Debug for Violation {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}: {} - {:?}", self.kind, self.description, self.messages)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::communication::message::MAX_GROUP_SIZE;
+
+  fn pair() -> MessageContent {
+    MessageContent::group(&['A', 'B'])
+  }
+
+  fn message(sender_id: char, msg_type: MessageType, id: u32, message_content: MessageContent) -> Message {
+    Message::new(sender_id, msg_type, id, message_content)
+  }
+
+  fn kinds(checker: &SafetyChecker) -> Vec<ViolationKind> {
+    checker.get_violations().iter().map(|violation| violation.kind).collect()
+  }
+
+  // A proposes to B and C, both promise and accept, A confirms and everyone decides the same pair
+  #[test]
+  fn clean_round_reports_nothing() {
+    let mut checker = SafetyChecker::new(false, true);
+    let ballot = Ballot::new(0, 'A').to_id();
+    for acceptor in ['B', 'C'] {
+      checker.observe(acceptor, &message('A', MessageType::PrepareRequest, ballot, pair()));
+      checker.observe('A', &message(acceptor, MessageType::PrepareResponse, ballot, pair()).with_ballot(ballot));
+      checker.observe(acceptor, &message('A', MessageType::AcceptRequest, ballot, pair()));
+      checker.observe('A', &message(acceptor, MessageType::Accepted, ballot, pair()));
+    }
+    checker.record_decision('A', 0, vec!['B', 'C'], message('A', MessageType::Accepted, ballot, pair()));
+    for acceptor in ['B', 'C'] {
+      checker.observe(acceptor, &message('A', MessageType::Confirm, 'A' as u32, pair()));
+      checker.record_decision(acceptor, 0, vec!['A'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    }
+    assert!(checker.get_violations().is_empty());
+  }
+
+  #[test]
+  fn detects_disagreement() {
+    let mut checker = SafetyChecker::new(false, true);
+    checker.record_decision('A', 0, vec!['B'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.record_decision('B', 0, vec!['A'], message('B', MessageType::Confirm, 'B' as u32, MessageContent::group(&['B', 'C'])));
+    assert_eq!(kinds(&checker), vec![ViolationKind::Disagreement]);
+  }
+
+  #[test]
+  fn same_group_in_either_order_is_no_disagreement() {
+    let mut checker = SafetyChecker::new(false, true);
+    let mut reversed = [None; MAX_GROUP_SIZE];
+    reversed[0] = Some('B');
+    reversed[1] = Some('A');
+    checker.record_decision('A', 0, vec!['B'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.record_decision('B', 0, vec!['A'], message('B', MessageType::Confirm, 'B' as u32, MessageContent::Group(reversed)));
+    assert!(checker.get_violations().is_empty());
+  }
+
+  // Only B of the three teammates accepted before A confirmed
+  #[test]
+  fn detects_no_majority() {
+    let mut checker = SafetyChecker::new(false, true);
+    let ballot = Ballot::new(0, 'A').to_id();
+    checker.observe('B', &message('A', MessageType::PrepareRequest, ballot, pair()));
+    checker.observe('A', &message('B', MessageType::Accepted, ballot, pair()));
+    checker.record_decision('A', 0, vec!['B', 'C', 'D'], message('A', MessageType::Accepted, ballot, pair()));
+    checker.observe('B', &message('A', MessageType::Confirm, 'A' as u32, pair()));
+    assert_eq!(kinds(&checker), vec![ViolationKind::NoMajority]);
+  }
+
+  #[test]
+  fn leader_confirms_need_no_majority() {
+    let mut checker = SafetyChecker::new(false, false);
+    checker.record_decision('A', 0, vec!['B', 'C', 'D'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.observe('B', &message('A', MessageType::Confirm, 'A' as u32, pair()));
+    assert!(checker.get_violations().is_empty());
+  }
+
+  // B promised C's higher ballot, then accepted A's lower one
+  #[test]
+  fn detects_broken_promise() {
+    let mut checker = SafetyChecker::new(false, true);
+    let low = Ballot::new(0, 'A').to_id();
+    let high = Ballot::new(1, 'C').to_id();
+    checker.observe('C', &message('B', MessageType::PrepareResponse, high, pair()).with_ballot(high));
+    checker.observe('A', &message('B', MessageType::Accepted, low, pair()));
+    assert_eq!(kinds(&checker), vec![ViolationKind::BrokenPromise]);
+  }
+
+  // A piggybacked promise carries the older value's ballot as its id, the promised ballot counts
+  #[test]
+  fn piggybacked_promise_counts_its_ballot() {
+    let mut checker = SafetyChecker::new(false, true);
+    let low = Ballot::new(0, 'A').to_id();
+    let high = Ballot::new(1, 'C').to_id();
+    checker.observe('C', &message('B', MessageType::PrepareResponse, low, pair()).with_ballot(high));
+    checker.observe('A', &message('B', MessageType::Accepted, low, pair()));
+    assert_eq!(kinds(&checker), vec![ViolationKind::BrokenPromise]);
+  }
+
+  #[test]
+  #[should_panic(expected = "Safety violation")]
+  fn panics_on_violation() {
+    let mut checker = SafetyChecker::new(true, true);
+    checker.record_decision('A', 0, vec!['B'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.record_decision('B', 0, vec!['A'], message('B', MessageType::Confirm, 'B' as u32, MessageContent::group(&['B', 'C'])));
+  }
+
+  // Without panicking the run goes on and every conflicting robot is on record
+  #[test]
+  fn records_conflicting_decisions_without_panicking() {
+    let mut checker = SafetyChecker::new(false, true);
+    let conflicting = MessageContent::group(&['B', 'C']);
+    checker.record_decision('A', 0, vec!['B', 'C'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.record_decision('B', 0, vec!['A', 'C'], message('B', MessageType::Confirm, 'B' as u32, conflicting));
+    checker.record_decision('C', 0, vec!['A', 'B'], message('C', MessageType::Confirm, 'C' as u32, conflicting));
+    let violations = checker.get_violations();
+    assert_eq!(violations.len(), 2);
+    assert!(violations.iter().all(|violation| violation.kind == ViolationKind::Disagreement));
+    assert!(violations.iter().any(|violation| violation.messages.iter().any(|message| message.message_content == conflicting)));
+  }
+
+  // Decisions in different epochs never conflict
+  #[test]
+  fn decisions_of_other_epochs_do_not_conflict() {
+    let mut checker = SafetyChecker::new(true, true);
+    checker.record_decision('A', 0, vec!['B'], message('A', MessageType::Confirm, 'A' as u32, pair()));
+    checker.record_decision('B', 1, vec!['A'], message('B', MessageType::Confirm, 'B' as u32, MessageContent::group(&['B', 'C'])));
+    assert!(checker.get_violations().is_empty());
+  }
+}
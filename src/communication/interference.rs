@@ -0,0 +1,56 @@
+use crate::communication::message::Message;
+use crate::communication::range::RangeModel;
+use crate::util::Coord;
+
+// Cross-team mechanics: enemies overhearing this team's messages and jamming them
+pub struct Interference {
+  // How far this team's robots can overhear enemy messages, None is deaf
+  eavesdrop_radius: Option<usize>,
+  jam_radius: usize,
+  jam_drop_probability: f64,
+  // Messages sent this turn with the position of their sender
+  broadcasts: Vec<(Coord, Message)>,
+  // Enemy robots that jammed last turn
+  jammers: Vec<Coord>,
+}
+
+// Constructor and getters
+impl Interference {
+  pub fn new(eavesdrop_radius: Option<usize>, jam_radius: usize, jam_drop_probability: f64) -> Interference {
+    Self {
+      eavesdrop_radius,
+      jam_radius,
+      jam_drop_probability,
+      broadcasts: Vec::new(),
+      jammers: Vec::new(),
+    }
+  }
+
+  pub fn get_eavesdrop_radius(&self) -> Option<usize> {
+    self.eavesdrop_radius
+  }
+}
+
+// Interference logic
+impl Interference {
+  pub fn record_broadcast(&mut self, sender_coord: Coord, message: Message) {
+    self.broadcasts.push((sender_coord, message));
+  }
+
+  pub fn take_broadcasts(&mut self) -> Vec<(Coord, Message)> {
+    self.broadcasts.drain(..).collect()
+  }
+
+  pub fn set_jammers(&mut self, jammers: Vec<Coord>) {
+    self.jammers = jammers;
+  }
+
+  // A message is lost if either end is close to a jammer and the jam succeeds
+  pub fn is_jammed(&self, sender_coord: Option<Coord>, receiver_coord: Option<Coord>) -> bool {
+    let near_jammer = |coord: Option<Coord>| match coord {
+      Some(coord) => self.jammers.iter().any(|&jammer| RangeModel::within_radius(jammer, coord, self.jam_radius)),
+      None => false,
+    };
+    (near_jammer(sender_coord) || near_jammer(receiver_coord)) && rand::random_bool(self.jam_drop_probability)
+  }
+}
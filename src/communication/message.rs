@@ -2,6 +2,14 @@ use std::collections::{HashMap};
 use std::fmt::{Debug, Display, Formatter};
 use colored::Colorize;
 use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::communication::network::{NetworkModel, ReorderPolicy};
+use crate::communication::range::{OutOfRangePolicy, RangeModel};
+use crate::communication::network::OverBudgetPolicy;
+use crate::communication::checker::SafetyChecker;
+use crate::communication::partition::PartitionSchedule;
+use crate::communication::interference::Interference;
+use crate::metrics::TeamMetrics;
 use crate::robot::Direction;
 use crate::util::Coord;
 
@@ -18,14 +26,52 @@ pub enum MessageType {
   Ack,
   Done,
   GetOut,
+  KnowledgeSync,
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum MessageContent {
   Coord(Option<Coord>, Option<u8>),
-  Pair(char, char),
+  // Gold pile, its number of bars and their total value
+  Gold(Coord, u8, u16),
+  // Robots picking up one bar together, sorted with the unused places at the end
+  Group([Option<char>; MAX_GROUP_SIZE]),
   Direction(Direction),
   TurnReq(Direction, Coord),
+  // Cells that changed since the sender last saw them, and the turn they were observed
+  Observations([Option<Observation>; MAX_OBSERVATIONS], u32),
+}
+
+// Coordinate, number of bars and their total value, red robots and blue robots
+pub type Observation = (Coord, Option<(u8, u16)>, u8, u8);
+
+// Most cells a single knowledge sync message carries
+pub const MAX_OBSERVATIONS: usize = 8;
+
+// Largest group a consensus can put together for one bar
+pub const MAX_GROUP_SIZE: usize = 8;
+
+impl MessageContent {
+  // The same robots always make the same group, whatever order they come in
+  pub fn group(ids: &[char]) -> MessageContent {
+    let mut sorted = ids.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    assert!(sorted.len() <= MAX_GROUP_SIZE, "Groups have at most {} robots, got {:?}", MAX_GROUP_SIZE, sorted);
+    let mut members = [None; MAX_GROUP_SIZE];
+    for (slot, id) in members.iter_mut().zip(sorted) {
+      *slot = Some(id);
+    }
+    MessageContent::Group(members)
+  }
+
+  // Robots of a group, empty for any other content
+  pub fn members(&self) -> Vec<char> {
+    match self {
+      MessageContent::Group(members) => members.iter().flatten().copied().collect(),
+      _ => Vec::new(),
+    }
+  }
 }
 
 #[derive(PartialEq, Hash, Eq, Clone, Copy)]
@@ -34,21 +80,103 @@ pub struct Message {
   pub msg_type: MessageType,
   pub id: u32,
   pub message_content: MessageContent,
-  pub timer: u8,
+  pub sent_turn: u32,
+  pub ready_turn: u32,
+  pub epoch: u32,
+  pub ttl: Option<u32>,
+  // Ballot a PrepareResponse promises, its id is the ballot of the value it carries. Zero for other messages
+  pub ballot: u32,
+  // Zero when the team does not authenticate its messages
+  pub mac: u64,
 }
 
 impl Message {
+  // The turns are stamped by the message board, the epoch and mac by the robot once the message is sent
   pub fn new(sender_id: char, msg_type: MessageType, id: u32, message_content: MessageContent) -> Message {
-    let mut rng = rand::rng();
-    let timer = rng.random_range(0..=3);
     Self {
       sender_id,
       msg_type,
       id,
       message_content,
-      timer,
+      sent_turn: 0,
+      ready_turn: 0,
+      epoch: 0,
+      ttl: None,
+      ballot: 0,
+      mac: 0,
     }
   }
+
+  pub fn with_ballot(mut self, ballot: u32) -> Message {
+    self.ballot = ballot;
+    self
+  }
+
+  // Estimated wire size: sender, type, id, send turn, epoch, ttl, ballot if set and mac if signed, followed by the content
+  pub fn size_bytes(&self) -> u32 {
+    let ballot = if self.ballot != 0 { 4 } else { 0 };
+    let mac = if self.mac != 0 { 8 } else { 0 };
+    let header = 1 + 1 + 4 + 4 + 4 + 4 + ballot + mac;
+    let content = match self.message_content {
+      MessageContent::Coord(_, _) => 1 + 4 + 1 + 1,
+      MessageContent::Gold(_, _, _) => 4 + 1 + 2,
+      MessageContent::Group(members) => 1 + members.iter().flatten().count() as u32,
+      MessageContent::Direction(_) => 1,
+      MessageContent::TurnReq(_, _) => 1 + 4,
+      MessageContent::Observations(observations, _) => 1 + observations.iter().flatten().count() as u32 * (4 + 4 + 1 + 1) + 4,
+    };
+    header + 1 + content
+  }
+
+  pub fn is_ready(&self, current_turn: u32) -> bool {
+    self.ready_turn <= current_turn
+  }
+
+  pub fn is_expired(&self, current_turn: u32) -> bool {
+    match self.ttl {
+      Some(ttl) => current_turn > self.sent_turn + ttl,
+      None => false,
+    }
+  }
+}
+
+impl MessageType {
+  // Messages that only make sense within the round they were sent in
+  pub fn is_round_scoped(&self) -> bool {
+    !matches!(self, MessageType::Simple | MessageType::Done | MessageType::KnowledgeSync)
+  }
+}
+
+impl MessageType {
+  // Lower values are retrieved first under the priority policy
+  pub fn priority(&self) -> u8 {
+    match self {
+      MessageType::Confirm => 0,
+      MessageType::Accepted => 1,
+      MessageType::AcceptRequest => 2,
+      MessageType::PrepareResponse => 3,
+      MessageType::Nack => 4,
+      MessageType::PrepareRequest => 5,
+      MessageType::GetOut => 6,
+      MessageType::Done => 7,
+      MessageType::Ack => 8,
+      MessageType::Request => 9,
+      MessageType::Simple => 10,
+      MessageType::KnowledgeSync => 11,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RetrievalPolicy {
+  // Peek at one random message per turn, nothing is retrieved if it is not ready yet
+  Random,
+  // Oldest ready message by arrival
+  Fifo,
+  // Ready message with the most urgent type, oldest first among equals
+  Priority,
+  // Every ready message at once, by arrival
+  DrainReady,
 }
 
 #[derive(Default)]
@@ -65,7 +193,10 @@ impl MessageBox {
     }
   }
 
-  pub fn update_messages(&mut self) {
+  pub fn update_messages(&mut self, reorder_policy: ReorderPolicy) {
+    if reorder_policy == ReorderPolicy::Shuffle {
+      self.new_messages.shuffle(&mut rand::rng());
+    }
     self.current_messages.extend(self.new_messages.drain(..));
   }
 
@@ -73,42 +204,74 @@ impl MessageBox {
     self.new_messages.push(message);
   }
 
-  pub fn retrieve_messages(&mut self) -> Option<Message> {
-    if !self.current_messages.is_empty() {
-      let mut rng = rand::rng();
-      let random_index = rng.random_range(0..self.current_messages.len());
-      let random_message = self.current_messages.get_mut(random_index);
-      let mut return_message = None;
-      let mut message_available = false;
-      match random_message {
-        Some(message) => {
-          if message.timer == 0 {
-            return_message = Some(message.clone());
-            message_available = true;
-          } else {
-            message.timer -= 1;
-          }
-        },
-        None => {}
-      }
-      if message_available {
-        self.current_messages.remove(random_index);
+  pub fn retrieve_messages(&mut self, retrieval_policy: RetrievalPolicy, current_turn: u32) -> Vec<Message> {
+    let ready_index = match retrieval_policy {
+      RetrievalPolicy::Random => {
+        if self.current_messages.is_empty() {
+          None
+        } else {
+          let random_index = rand::rng().random_range(0..self.current_messages.len());
+          Some(random_index).filter(|&index| self.current_messages[index].is_ready(current_turn))
+        }
+      },
+      RetrievalPolicy::Fifo => {
+        self.current_messages.iter().position(|message| message.is_ready(current_turn))
+      },
+      RetrievalPolicy::Priority => {
+        self.current_messages.iter()
+          .enumerate()
+          .filter(|(_, message)| message.is_ready(current_turn))
+          .min_by_key(|(index, message)| (message.msg_type.priority(), *index))
+          .map(|(index, _)| index)
+      },
+      RetrievalPolicy::DrainReady => {
+        let (ready, waiting): (Vec<Message>, Vec<Message>) = self.current_messages.drain(..).partition(|message| message.is_ready(current_turn));
+        self.current_messages = waiting;
+        return ready;
       }
-      return_message
-    } else {
-      None
+    };
+    match ready_index {
+      Some(index) => vec![self.current_messages.remove(index)],
+      None => Vec::new(),
     }
   }
 }
 
 pub struct MessageBoard {
-  message_board: HashMap<char, MessageBox>
+  message_board: HashMap<char, MessageBox>,
+  network_model: NetworkModel,
+  range_model: RangeModel,
+  positions: HashMap<char, Coord>,
+  held_messages: Vec<(char, Message)>,
+  sent_this_turn: HashMap<char, u32>,
+  queued_sends: Vec<(char, Message)>,
+  retrieval_policy: RetrievalPolicy,
+  current_turn: u32,
+  // Latest round any robot of the team has started, epochs are taken from it
+  round: u32,
+  metrics: TeamMetrics,
+  checker: Option<SafetyChecker>,
+  partition_schedule: PartitionSchedule,
+  interference: Interference,
 }
 
 impl MessageBoard {
-  pub fn new() -> MessageBoard {
+  pub fn new(network_model: NetworkModel, range_model: RangeModel, retrieval_policy: RetrievalPolicy, checker: Option<SafetyChecker>, partition_schedule: PartitionSchedule, interference: Interference) -> MessageBoard {
     Self {
       message_board: HashMap::new(),
+      network_model,
+      range_model,
+      positions: HashMap::new(),
+      held_messages: Vec::new(),
+      sent_this_turn: HashMap::new(),
+      queued_sends: Vec::new(),
+      retrieval_policy,
+      current_turn: 0,
+      round: 0,
+      metrics: TeamMetrics::new(),
+      checker,
+      partition_schedule,
+      interference,
     }
   }
 
@@ -120,9 +283,153 @@ impl MessageBoard {
     &mut self.message_board
   }
 
+  pub fn get_network_model(&self) -> &NetworkModel {
+    &self.network_model
+  }
+
+  pub fn set_position(&mut self, id: char, coord: Coord) {
+    self.positions.insert(id, coord);
+  }
+
+  pub fn get_metrics(&self) -> &TeamMetrics {
+    &self.metrics
+  }
+
+  pub fn get_current_turn(&self) -> u32 {
+    self.current_turn
+  }
+
+  // The first robot to leave the team's current round starts the next one, robots leaving it after
+  // them, or still behind, join that round instead of counting their own
+  pub fn next_round(&mut self, epoch: u32) -> u32 {
+    if epoch >= self.round {
+      self.round += 1;
+    }
+    self.round
+  }
+
+  pub fn get_metrics_mut(&mut self) -> &mut TeamMetrics {
+    &mut self.metrics
+  }
+
+  pub fn get_checker(&self) -> Option<&SafetyChecker> {
+    self.checker.as_ref()
+  }
+
+  pub fn get_interference(&mut self) -> &mut Interference {
+    &mut self.interference
+  }
+
+  pub fn get_partition_schedule(&self) -> &PartitionSchedule {
+    &self.partition_schedule
+  }
+
+  pub fn report_decision(&mut self, robot_id: char, epoch: u32, cluster: Vec<char>, message: Message) {
+    self.partition_schedule.record_decision(robot_id, epoch, message, self.current_turn);
+    if let Some(checker) = self.checker.as_mut() {
+      checker.record_decision(robot_id, epoch, cluster, message);
+    }
+  }
+
+  pub fn retrieve(&mut self, id: char) -> Vec<Message> {
+    let current_turn = self.current_turn;
+    let messages = match self.message_board.get_mut(&id) {
+      Some(message_box) => message_box.retrieve_messages(self.retrieval_policy, current_turn),
+      None => Vec::new(),
+    };
+    for message in messages.iter() {
+      self.metrics.record_delivery(current_turn - message.sent_turn);
+    }
+    messages
+  }
+
+  // Puts a message on the air once, however many teammates it is addressed to
+  pub fn broadcast(&mut self, mut message: Message) {
+    if let Some(&sender_coord) = self.positions.get(&message.sender_id) {
+      message.sent_turn = self.current_turn;
+      self.interference.record_broadcast(sender_coord, message);
+    }
+  }
+
+  pub fn send(&mut self, receiver_id: char, message: Message) {
+    if !self.try_send(receiver_id, message) {
+      match self.network_model.get_bandwidth().over_budget_policy {
+        OverBudgetPolicy::Queue if self.queued_sends.len() >= self.network_model.get_bandwidth().queue_limit => self.metrics.queue_overflows += 1,
+        OverBudgetPolicy::Queue => {
+          self.queued_sends.push((receiver_id, message));
+          self.metrics.queued_sends += 1;
+        },
+        OverBudgetPolicy::Reject => self.metrics.dropped_sends += 1,
+      }
+    }
+  }
+
+  fn try_send(&mut self, receiver_id: char, mut message: Message) -> bool {
+    let sent = self.sent_this_turn.entry(message.sender_id).or_insert(0);
+    if let Some(send_budget) = self.network_model.get_bandwidth().send_budget && *sent >= send_budget {
+      return false;
+    }
+    *sent += 1;
+    message.sent_turn = self.current_turn;
+    self.metrics.record_send(message.msg_type, message.size_bytes());
+    if let Some(checker) = self.checker.as_mut() {
+      checker.observe(receiver_id, &message);
+    }
+    self.deliver(receiver_id, message);
+    true
+  }
+
+  fn deliver(&mut self, receiver_id: char, message: Message) {
+    if self.partition_schedule.cuts(receiver_id, &message, self.current_turn) {
+      self.metrics.partitioned += 1;
+      return;
+    }
+    if self.interference.is_jammed(self.positions.get(&message.sender_id).copied(), self.positions.get(&receiver_id).copied()) {
+      self.metrics.jammed += 1;
+      return;
+    }
+    match self.range_model.route(message.sender_id, receiver_id, &self.positions) {
+      Some(hops) => self.transmit(receiver_id, message, hops),
+      None => {
+        if self.range_model.get_out_of_range_policy() == OutOfRangePolicy::Hold {
+          self.held_messages.push((receiver_id, message));
+        } else {
+          self.metrics.lost_in_transit += 1;
+        }
+      }
+    }
+  }
+
+  fn transmit(&mut self, receiver_id: char, message: Message, hops: usize) {
+    let transmitted_messages = self.network_model.transmit(receiver_id, message, self.current_turn);
+    if transmitted_messages.is_empty() {
+      self.metrics.lost_in_transit += 1;
+    }
+    for mut transmitted in transmitted_messages {
+      // Every relay adds a turn of delay
+      transmitted.ready_turn += (hops - 1) as u32;
+      self.message_board.entry(receiver_id).or_default().send_messages(transmitted);
+    }
+  }
+
+  // Starts the next turn, so held and queued messages go out stamped with it
   pub fn update(&mut self) {
+    self.current_turn += 1;
+    let held_messages: Vec<(char, Message)> = self.held_messages.drain(..).collect();
+    for (receiver_id, message) in held_messages {
+      self.deliver(receiver_id, message);
+    }
+    // Queued sends use up the budget of the new turn
+    self.sent_this_turn.clear();
+    let queued_sends: Vec<(char, Message)> = self.queued_sends.drain(..).collect();
+    for (receiver_id, message) in queued_sends {
+      if !self.try_send(receiver_id, message) {
+        self.queued_sends.push((receiver_id, message));
+      }
+    }
+    let reorder_policy = self.network_model.get_reorder_policy();
     for message_box in self.message_board.values_mut() {
-      message_box.update_messages();
+      message_box.update_messages(reorder_policy);
     }
   }
 }
@@ -131,17 +438,30 @@ impl MessageBoard {
 impl Debug for MessageContent {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
     match self {
-      MessageContent::Pair(a, b) => {
-        write!(f, "Pair {} {}", a, b)
+      MessageContent::Group(_) => {
+        write!(f, "Group {:?}", self.members())
       },
       MessageContent::Coord(coord, gold_amount) => {
         write!(f, "{:?} has {:?} golds", coord, gold_amount)
       },
+      MessageContent::Gold(coord, bars, value) => {
+        write!(f, "{:?} has {} golds worth {}", coord, bars, value)
+      },
       MessageContent::Direction(direction) => {
         write!(f, "{:?}", direction)
       },
       MessageContent::TurnReq(direction, coord) => {
         write!(f, "{:?} has {:?} coords", direction, coord)
+      },
+      MessageContent::Observations(observations, turn) => {
+        write!(f, "At turn {}:", turn)?;
+        for (coord, gold, red_robots, blue_robots) in observations.iter().flatten() {
+          match gold {
+            Some((bars, value)) => write!(f, " {:?} has {} golds worth {}, {} red and {} blue robots;", coord, bars, value, red_robots, blue_robots)?,
+            None => write!(f, " {:?} has no gold, {} red and {} blue robots;", coord, red_robots, blue_robots)?,
+          }
+        }
+        Ok(())
       }
     }
   }
@@ -150,7 +470,7 @@ impl Debug for MessageContent {
 
 impl Debug for Message {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    write!(f, "{}: {:?} - {:?} from {} ({})", self.id, self.msg_type, self.message_content, self.sender_id, self.timer)
+    write!(f, "{}: {:?} - {:?} from {} ({}, epoch {})", self.id, self.msg_type, self.message_content, self.sender_id, self.ready_turn, self.epoch)
   }
 }
 
@@ -184,4 +504,230 @@ impl Display for MessageBoard {
     write!(f, "")
   }
   
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+  use super::*;
+  use crate::communication::network::{Bandwidth, DelayDistribution, LinkModel};
+  use crate::communication::range::CommunicationRange;
+
+  const LINK: LinkModel = LinkModel {
+    delay: DelayDistribution::Fixed(0),
+    drop_probability: 0.0,
+    duplication_probability: 0.0,
+    corruption_probability: 0.0,
+  };
+
+  const UNLIMITED: Bandwidth = Bandwidth {
+    send_budget: None,
+    over_budget_policy: OverBudgetPolicy::Queue,
+    queue_limit: 64,
+  };
+
+  fn board(link: LinkModel, reorder_policy: ReorderPolicy, bandwidth: Bandwidth, retrieval_policy: RetrievalPolicy) -> MessageBoard {
+    let range_model = RangeModel::new(CommunicationRange::Unlimited, OutOfRangePolicy::Drop, 0, HashSet::new());
+    let mut board = MessageBoard::new(NetworkModel::new(link, reorder_policy, bandwidth), range_model, retrieval_policy, None, PartitionSchedule::new(Vec::new()), Interference::new(None, 0, 0.0));
+    board.insert('A', MessageBox::new());
+    board.insert('B', MessageBox::new());
+    board
+  }
+
+  // A and B two cells apart with a one cell radius, nobody in between to relay
+  fn out_of_range_board(policy: OutOfRangePolicy) -> MessageBoard {
+    let range_model = RangeModel::new(CommunicationRange::Radius(1), policy, 1, HashSet::new());
+    let mut board = MessageBoard::new(NetworkModel::new(LINK, ReorderPolicy::Preserve, UNLIMITED), range_model, RetrievalPolicy::Fifo, None, PartitionSchedule::new(Vec::new()), Interference::new(None, 0, 0.0));
+    board.insert('A', MessageBox::new());
+    board.insert('B', MessageBox::new());
+    board.set_position('A', Coord::new(0, 0));
+    board.set_position('B', Coord::new(2, 0));
+    board
+  }
+
+  fn simple(id: u32) -> Message {
+    Message::new('A', MessageType::Simple, id, MessageContent::Coord(None, None))
+  }
+
+  // A box holding messages of the given types that become ready on the given turns, ids by arrival
+  fn mailbox(messages: &[(MessageType, u32)]) -> MessageBox {
+    let mut message_box = MessageBox::new();
+    for (id, (msg_type, ready_turn)) in messages.iter().enumerate() {
+      let mut message = Message::new('A', *msg_type, id as u32, MessageContent::Coord(None, None));
+      message.ready_turn = *ready_turn;
+      message_box.send_messages(message);
+    }
+    message_box.update_messages(ReorderPolicy::Preserve);
+    message_box
+  }
+
+  fn ids(messages: &[Message]) -> Vec<u32> {
+    messages.iter().map(|message| message.id).collect()
+  }
+
+  fn retrieve_all(board: &mut MessageBoard, id: char) -> Vec<Message> {
+    let mut messages = Vec::new();
+    loop {
+      let retrieved = board.retrieve(id);
+      if retrieved.is_empty() {
+        return messages;
+      }
+      messages.extend(retrieved);
+    }
+  }
+
+  #[test]
+  fn queued_sends_go_out_stamped_with_the_next_turn() {
+    let bandwidth = Bandwidth { send_budget: Some(1), ..UNLIMITED };
+    let mut board = board(LINK, ReorderPolicy::Preserve, bandwidth, RetrievalPolicy::Fifo);
+    board.send('B', simple(1));
+    board.send('B', simple(2));
+    assert_eq!(board.get_metrics().queued_sends, 1);
+    board.update();
+    board.update();
+    let received = retrieve_all(&mut board, 'B');
+    assert_eq!(received.iter().map(|message| (message.id, message.sent_turn)).collect::<Vec<_>>(), vec![(1, 0), (2, 1)]);
+  }
+
+  #[test]
+  fn full_queue_drops_further_sends() {
+    let bandwidth = Bandwidth { send_budget: Some(0), queue_limit: 2, ..UNLIMITED };
+    let mut board = board(LINK, ReorderPolicy::Preserve, bandwidth, RetrievalPolicy::Fifo);
+    for id in 0..5 {
+      board.send('B', simple(id));
+    }
+    assert_eq!(board.get_metrics().queued_sends, 2);
+    assert_eq!(board.get_metrics().queue_overflows, 3);
+  }
+
+  // Stamped with the turn it left the queue, a one turn TTL still covers the turn it arrives in
+  #[test]
+  fn queued_sends_do_not_expire_early() {
+    let bandwidth = Bandwidth { send_budget: Some(1), ..UNLIMITED };
+    let mut board = board(LINK, ReorderPolicy::Preserve, bandwidth, RetrievalPolicy::Fifo);
+    board.send('B', simple(1));
+    let mut queued = simple(2);
+    queued.ttl = Some(1);
+    board.send('B', queued);
+    board.update();
+    board.update();
+    let turn = board.get_current_turn();
+    let received = retrieve_all(&mut board, 'B');
+    assert_eq!(received.len(), 2);
+    assert!(!received[1].is_expired(turn));
+  }
+
+  #[test]
+  fn robots_leaving_a_round_share_the_next_epoch() {
+    let mut board = board(LINK, ReorderPolicy::Preserve, UNLIMITED, RetrievalPolicy::Fifo);
+    assert_eq!(board.next_round(0), 1);
+    assert_eq!(board.next_round(0), 1);
+    assert_eq!(board.next_round(1), 2);
+    // A robot that missed a few rounds catches up instead of drifting
+    assert_eq!(board.next_round(0), 2);
+  }
+
+  #[test]
+  fn groups_are_the_same_whatever_the_order() {
+    assert_eq!(MessageContent::group(&['C', 'A', 'B']), MessageContent::group(&['A', 'B', 'C']));
+    assert_eq!(MessageContent::group(&['C', 'A', 'B', 'A']).members(), vec!['A', 'B', 'C']);
+    assert_ne!(MessageContent::group(&['A', 'B']), MessageContent::group(&['A', 'B', 'C']));
+    assert!(MessageContent::Coord(None, None).members().is_empty());
+  }
+
+  #[test]
+  fn out_of_range_messages_are_dropped() {
+    let mut board = out_of_range_board(OutOfRangePolicy::Drop);
+    board.send('B', simple(1));
+    board.set_position('B', Coord::new(1, 0));
+    board.update();
+    assert!(retrieve_all(&mut board, 'B').is_empty());
+    assert_eq!(board.get_metrics().lost_in_transit, 1);
+  }
+
+  #[test]
+  fn held_messages_go_out_once_back_in_range() {
+    let mut board = out_of_range_board(OutOfRangePolicy::Hold);
+    board.send('B', simple(1));
+    board.update();
+    assert!(retrieve_all(&mut board, 'B').is_empty());
+    board.set_position('B', Coord::new(1, 0));
+    // Released at the start of the turn, it arrives a turn later like any other send
+    board.update();
+    board.update();
+    assert_eq!(retrieve_all(&mut board, 'B').iter().map(|message| message.id).collect::<Vec<_>>(), vec![1]);
+  }
+
+  #[test]
+  fn fifo_takes_the_oldest_ready_message() {
+    let mut message_box = mailbox(&[(MessageType::Simple, 3), (MessageType::Simple, 1), (MessageType::Simple, 1)]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Fifo, 1)), vec![1]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Fifo, 1)), vec![2]);
+    assert!(message_box.retrieve_messages(RetrievalPolicy::Fifo, 1).is_empty());
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Fifo, 3)), vec![0]);
+  }
+
+  #[test]
+  fn priority_takes_the_most_urgent_ready_message() {
+    let mut message_box = mailbox(&[(MessageType::Simple, 0), (MessageType::PrepareRequest, 0), (MessageType::Confirm, 2), (MessageType::PrepareRequest, 0)]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Priority, 0)), vec![1]);
+    // The confirm is more urgent but not ready yet
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Priority, 0)), vec![3]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Priority, 2)), vec![2]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Priority, 2)), vec![0]);
+  }
+
+  #[test]
+  fn drain_ready_takes_every_ready_message_at_once() {
+    let mut message_box = mailbox(&[(MessageType::Simple, 0), (MessageType::Confirm, 2), (MessageType::Simple, 1)]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::DrainReady, 1)), vec![0, 2]);
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::DrainReady, 2)), vec![1]);
+    assert!(message_box.retrieve_messages(RetrievalPolicy::DrainReady, 2).is_empty());
+  }
+
+  #[test]
+  fn random_never_takes_a_message_that_is_not_ready() {
+    let mut message_box = mailbox(&[(MessageType::Simple, 5)]);
+    for _ in 0..20 {
+      assert!(message_box.retrieve_messages(RetrievalPolicy::Random, 4).is_empty());
+    }
+    assert_eq!(ids(&message_box.retrieve_messages(RetrievalPolicy::Random, 5)), vec![0]);
+  }
+
+  #[test]
+  fn a_duplicated_message_is_delivered_twice() {
+    let link = LinkModel { duplication_probability: 1.0, ..LINK };
+    let mut board = board(link, ReorderPolicy::Preserve, UNLIMITED, RetrievalPolicy::Fifo);
+    board.send('B', simple(1));
+    board.update();
+    assert_eq!(ids(&retrieve_all(&mut board, 'B')), vec![1, 1]);
+  }
+
+  #[test]
+  fn a_message_on_a_slow_link_is_overtaken() {
+    let slow = LinkModel { delay: DelayDistribution::Fixed(2), ..LINK };
+    let network_model = NetworkModel::new(LINK, ReorderPolicy::Preserve, UNLIMITED).with_link_override('C', 'B', slow);
+    let range_model = RangeModel::new(CommunicationRange::Unlimited, OutOfRangePolicy::Drop, 0, HashSet::new());
+    let mut board = MessageBoard::new(network_model, range_model, RetrievalPolicy::Fifo, None, PartitionSchedule::new(Vec::new()), Interference::new(None, 0, 0.0));
+    board.insert('B', MessageBox::new());
+    board.send('B', simple(1));
+    board.send('B', Message::new('C', MessageType::Simple, 2, MessageContent::Coord(None, None)));
+    board.send('B', simple(3));
+    board.update();
+    assert_eq!(ids(&retrieve_all(&mut board, 'B')), vec![1, 3]);
+    board.update();
+    board.update();
+    assert_eq!(ids(&retrieve_all(&mut board, 'B')), vec![2]);
+  }
+
+  #[test]
+  fn shuffled_messages_all_arrive() {
+    let mut board = board(LINK, ReorderPolicy::Shuffle, UNLIMITED, RetrievalPolicy::DrainReady);
+    for id in 0..10 {
+      board.send('B', simple(id));
+    }
+    board.update();
+    let mut received = ids(&retrieve_all(&mut board, 'B'));
+    received.sort();
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+  }
+}
@@ -1 +1,8 @@
-pub mod message;
\ No newline at end of file
+pub mod auth;
+pub mod ballot;
+pub mod checker;
+pub mod interference;
+pub mod message;
+pub mod network;
+pub mod partition;
+pub mod range;
\ No newline at end of file
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use rand::Rng;
+use crate::communication::message::{Message, MessageContent};
+
+#[derive(Clone, Copy, Debug)]
+pub enum DelayDistribution {
+  Fixed(u8),
+  // Inclusive range of turns
+  Uniform(u8, u8),
+  // Each extra turn of delay happens with the given probability, capped at the maximum
+  Geometric(f64, u8),
+}
+
+impl DelayDistribution {
+  pub fn sample(&self) -> u8 {
+    let mut rng = rand::rng();
+    match *self {
+      DelayDistribution::Fixed(delay) => delay,
+      DelayDistribution::Uniform(min, max) => rng.random_range(min..=max),
+      DelayDistribution::Geometric(p, max) => {
+        let mut delay = 0;
+        while delay < max && rng.random_bool(p) {
+          delay += 1;
+        }
+        delay
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReorderPolicy {
+  // Messages reach the inbox in the order they were sent
+  Preserve,
+  // Messages sent in the same turn reach the inbox in a random order
+  Shuffle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OverBudgetPolicy {
+  // Hold the send until the robot has budget again in a later turn
+  Queue,
+  Reject,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bandwidth {
+  // Messages a robot may send per turn, every receiver counts as one message
+  pub send_budget: Option<u32>,
+  pub over_budget_policy: OverBudgetPolicy,
+  // Sends the team may hold in its queue, further sends are dropped
+  pub queue_limit: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LinkModel {
+  pub delay: DelayDistribution,
+  pub drop_probability: f64,
+  pub duplication_probability: f64,
+  pub corruption_probability: f64,
+}
+
+pub struct NetworkModel {
+  default_link: LinkModel,
+  reorder_policy: ReorderPolicy,
+  link_overrides: HashMap<(char, char), LinkModel>,
+  bandwidth: Bandwidth,
+}
+
+// Constructor and getters
+impl NetworkModel {
+  pub fn new(default_link: LinkModel, reorder_policy: ReorderPolicy, bandwidth: Bandwidth) -> NetworkModel {
+    Self {
+      default_link,
+      reorder_policy,
+      link_overrides: HashMap::new(),
+      bandwidth,
+    }
+  }
+
+  pub fn with_link_override(mut self, sender_id: char, receiver_id: char, link: LinkModel) -> NetworkModel {
+    self.link_overrides.insert((sender_id, receiver_id), link);
+    self
+  }
+
+  pub fn get_link(&self, sender_id: char, receiver_id: char) -> &LinkModel {
+    self.link_overrides.get(&(sender_id, receiver_id)).unwrap_or(&self.default_link)
+  }
+
+  pub fn get_reorder_policy(&self) -> ReorderPolicy {
+    self.reorder_policy
+  }
+
+  pub fn get_bandwidth(&self) -> Bandwidth {
+    self.bandwidth
+  }
+}
+
+// Fault logic
+impl NetworkModel {
+  // Returns the copies of the message that make it onto the link, each with its own ready turn
+  pub fn transmit(&self, receiver_id: char, message: Message, current_turn: u32) -> Vec<Message> {
+    let link = self.get_link(message.sender_id, receiver_id);
+    let mut rng = rand::rng();
+    if rng.random_bool(link.drop_probability) {
+      return Vec::new();
+    }
+    let copies = if rng.random_bool(link.duplication_probability) { 2 } else { 1 };
+    let mut transmitted = Vec::new();
+    for _ in 0..copies {
+      let mut copy = message;
+      // Messages reach the inbox at the end of the turn they are sent in
+      copy.ready_turn = current_turn + 1 + link.delay.sample() as u32;
+      if rng.random_bool(link.corruption_probability) {
+        Self::corrupt(&mut copy);
+      }
+      transmitted.push(copy);
+    }
+    transmitted
+  }
+
+  fn corrupt(message: &mut Message) {
+    let mut rng = rand::rng();
+    let bit = rng.random_range(0..8);
+    match message.message_content {
+      MessageContent::Coord(coord, Some(gold_amount)) if rng.random_bool(0.5) => {
+        message.message_content = MessageContent::Coord(coord, Some(gold_amount ^ (1 << bit)));
+      },
+      MessageContent::Gold(coord, bars, value) if rng.random_bool(0.5) => {
+        message.message_content = MessageContent::Gold(coord, bars, value ^ (1 << bit));
+      },
+      _ => message.id ^= 1 << bit,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::communication::message::MessageType;
+
+  const LINK: LinkModel = LinkModel {
+    delay: DelayDistribution::Fixed(0),
+    drop_probability: 0.0,
+    duplication_probability: 0.0,
+    corruption_probability: 0.0,
+  };
+
+  const UNLIMITED: Bandwidth = Bandwidth {
+    send_budget: None,
+    over_budget_policy: OverBudgetPolicy::Queue,
+    queue_limit: 64,
+  };
+
+  fn network(link: LinkModel) -> NetworkModel {
+    NetworkModel::new(link, ReorderPolicy::Preserve, UNLIMITED)
+  }
+
+  fn simple() -> Message {
+    Message::new('A', MessageType::Simple, 7, MessageContent::Coord(None, Some(1)))
+  }
+
+  #[test]
+  fn a_clean_link_delivers_one_copy_at_the_end_of_the_turn() {
+    let transmitted = network(LINK).transmit('B', simple(), 4);
+    assert_eq!(transmitted.len(), 1);
+    assert_eq!(transmitted[0].ready_turn, 5);
+    assert_eq!(transmitted[0].message_content, simple().message_content);
+  }
+
+  #[test]
+  fn a_lossy_link_drops_the_message() {
+    let lossy = LinkModel { drop_probability: 1.0, ..LINK };
+    assert!(network(lossy).transmit('B', simple(), 0).is_empty());
+  }
+
+  #[test]
+  fn duplication_sends_two_copies() {
+    let link = LinkModel { delay: DelayDistribution::Fixed(2), duplication_probability: 1.0, ..LINK };
+    let transmitted = network(link).transmit('B', simple(), 0);
+    assert_eq!(transmitted.len(), 2);
+    assert!(transmitted.iter().all(|copy| copy.id == 7 && copy.ready_turn == 3));
+  }
+
+  #[test]
+  fn corruption_changes_the_message() {
+    let link = LinkModel { corruption_probability: 1.0, ..LINK };
+    for _ in 0..20 {
+      let copy = network(link).transmit('B', simple(), 0)[0];
+      assert!(copy.id != 7 || copy.message_content != simple().message_content);
+    }
+  }
+
+  #[test]
+  fn link_overrides_only_apply_to_their_pair() {
+    let lossy = LinkModel { drop_probability: 1.0, ..LINK };
+    let network = network(LINK).with_link_override('A', 'B', lossy);
+    assert!(network.transmit('B', simple(), 0).is_empty());
+    assert_eq!(network.transmit('C', simple(), 0).len(), 1);
+  }
+
+  #[test]
+  fn delays_stay_within_their_distribution() {
+    for _ in 0..50 {
+      assert!((2..=4).contains(&DelayDistribution::Uniform(2, 4).sample()));
+    }
+    assert_eq!(DelayDistribution::Geometric(0.0, 3).sample(), 0);
+    assert_eq!(DelayDistribution::Geometric(1.0, 3).sample(), 3);
+  }
+}
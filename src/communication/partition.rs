@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::communication::message::{Message, MessageContent};
+
+// Robots of different groups cannot message each other from `from_turn` until `until_turn` (exclusive).
+// Robots not listed in any group form one more group together.
+#[derive(Clone, Copy, Debug)]
+pub struct Partition {
+  pub from_turn: u32,
+  pub until_turn: u32,
+  pub groups: &'static [&'static [char]],
+}
+
+impl Partition {
+  pub fn is_active(&self, turn: u32) -> bool {
+    self.from_turn <= turn && turn < self.until_turn
+  }
+
+  fn group_of(&self, id: char) -> Option<usize> {
+    self.groups.iter().position(|group| group.contains(&id))
+  }
+
+  pub fn separates(&self, sender_id: char, receiver_id: char) -> bool {
+    self.group_of(sender_id) != self.group_of(receiver_id)
+  }
+}
+
+struct PartitionDecision {
+  robot_id: char,
+  turn: u32,
+  epoch: u32,
+  content: MessageContent,
+}
+
+pub struct PartitionSchedule {
+  partitions: Vec<Partition>,
+  // Messages cut and decisions made per partition
+  cut_messages: HashMap<usize, u32>,
+  decisions: HashMap<usize, Vec<PartitionDecision>>,
+}
+
+// Constructor
+impl PartitionSchedule {
+  pub fn new(partitions: Vec<Partition>) -> PartitionSchedule {
+    Self {
+      partitions,
+      cut_messages: HashMap::new(),
+      decisions: HashMap::new(),
+    }
+  }
+
+  fn active(&self, turn: u32) -> Option<usize> {
+    self.partitions.iter().position(|partition| partition.is_active(turn))
+  }
+}
+
+// Partition logic
+impl PartitionSchedule {
+  // Whether the message is cut off, counted against the partition that cut it
+  pub fn cuts(&mut self, receiver_id: char, message: &Message, turn: u32) -> bool {
+    match self.active(turn) {
+      Some(index) if self.partitions[index].separates(message.sender_id, receiver_id) => {
+        *self.cut_messages.entry(index).or_insert(0) += 1;
+        true
+      },
+      _ => false,
+    }
+  }
+
+  pub fn record_decision(&mut self, robot_id: char, epoch: u32, message: Message, turn: u32) {
+    if let Some(index) = self.active(turn) {
+      self.decisions.entry(index).or_default().push(PartitionDecision {
+        robot_id,
+        turn,
+        epoch,
+        content: message.message_content,
+      });
+    }
+  }
+}
+
+// Print functions
+impl Display for PartitionSchedule {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.partitions.is_empty() {
+      return write!(f, "  No partitions");
+    }
+    for (index, partition) in self.partitions.iter().enumerate() {
+      writeln!(f, "  Turns {}..{}: groups {:?} - {} messages cut", partition.from_turn, partition.until_turn, partition.groups, self.cut_messages.get(&index).unwrap_or(&0))?;
+      let decisions = self.decisions.get(&index).map(Vec::as_slice).unwrap_or(&[]);
+      let mut sides: Vec<Option<usize>> = decisions.iter().map(|decision| partition.group_of(decision.robot_id)).collect();
+      sides.sort();
+      sides.dedup();
+      if sides.is_empty() {
+        writeln!(f, "    No decisions")?;
+      }
+      for side in sides {
+        let side_decisions: Vec<String> = decisions.iter()
+          .filter(|decision| partition.group_of(decision.robot_id) == side)
+          .map(|decision| format!("{} decided {:?} at turn {} (epoch {})", decision.robot_id, decision.content, decision.turn, decision.epoch))
+          .collect();
+        match side {
+          Some(group) => writeln!(f, "    Side {:?}: {}", partition.groups[group], side_decisions.join(", "))?,
+          None => writeln!(f, "    Rest of the team: {}", side_decisions.join(", "))?,
+        }
+      }
+    }
+    write!(f, "")
+  }
+}
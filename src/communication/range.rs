@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::util::Coord;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommunicationRange {
+  Unlimited,
+  // Euclidean radius in cells
+  Radius(usize),
+  // Euclidean radius in cells, and no obstacle on the straight line between the robots
+  LineOfSight(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutOfRangePolicy {
+  Drop,
+  // Keep the message on the board until sender and receiver are back in range
+  Hold,
+}
+
+pub struct RangeModel {
+  range: CommunicationRange,
+  out_of_range_policy: OutOfRangePolicy,
+  relay_max_hops: usize,
+  obstacles: HashSet<Coord>,
+}
+
+// Constructor and getters
+impl RangeModel {
+  pub fn new(range: CommunicationRange, out_of_range_policy: OutOfRangePolicy, relay_max_hops: usize, obstacles: HashSet<Coord>) -> RangeModel {
+    Self {
+      range,
+      out_of_range_policy,
+      relay_max_hops,
+      obstacles,
+    }
+  }
+
+  pub fn get_out_of_range_policy(&self) -> OutOfRangePolicy {
+    self.out_of_range_policy
+  }
+}
+
+// Reachability logic
+impl RangeModel {
+  pub fn in_range(&self, from: Coord, to: Coord) -> bool {
+    match self.range {
+      CommunicationRange::Unlimited => true,
+      CommunicationRange::Radius(radius) => Self::within_radius(from, to, radius),
+      CommunicationRange::LineOfSight(radius) => Self::within_radius(from, to, radius) && self.line_of_sight(from, to),
+    }
+  }
+
+  // Number of hops needed to reach the receiver, relaying through teammates if allowed
+  pub fn route(&self, sender_id: char, receiver_id: char, positions: &HashMap<char, Coord>) -> Option<usize> {
+    if self.range == CommunicationRange::Unlimited {
+      return Some(1);
+    }
+    let (Some(&from), Some(&to)) = (positions.get(&sender_id), positions.get(&receiver_id)) else {
+      return Some(1);
+    };
+    if self.in_range(from, to) {
+      return Some(1);
+    }
+    let mut visited: HashSet<char> = HashSet::from([sender_id]);
+    let mut queue: VecDeque<(char, usize)> = VecDeque::from([(sender_id, 0)]);
+    while let Some((relay_id, hops)) = queue.pop_front() {
+      let relay_coord = positions[&relay_id];
+      if relay_id != sender_id && self.in_range(relay_coord, to) {
+        return Some(hops + 1);
+      }
+      if hops == self.relay_max_hops {
+        continue;
+      }
+      for (&next_id, &next_coord) in positions.iter() {
+        if next_id != receiver_id && !visited.contains(&next_id) && self.in_range(relay_coord, next_coord) {
+          visited.insert(next_id);
+          queue.push_back((next_id, hops + 1));
+        }
+      }
+    }
+    None
+  }
+
+  pub fn within_radius(from: Coord, to: Coord, radius: usize) -> bool {
+    let dx = from.x.abs_diff(to.x);
+    let dy = from.y.abs_diff(to.y);
+    dx * dx + dy * dy <= radius * radius
+  }
+
+  // Bresenham line between the two cells, endpoints never block
+  fn line_of_sight(&self, from: Coord, to: Coord) -> bool {
+    let (mut x, mut y) = (from.x as i64, from.y as i64);
+    let (end_x, end_y) = (to.x as i64, to.y as i64);
+    let dx = (end_x - x).abs();
+    let dy = -(end_y - y).abs();
+    let step_x = if x < end_x { 1 } else { -1 };
+    let step_y = if y < end_y { 1 } else { -1 };
+    let mut error = dx + dy;
+    while (x, y) != (end_x, end_y) {
+      let double_error = 2 * error;
+      if double_error >= dy {
+        error += dy;
+        x += step_x;
+      }
+      if double_error <= dx {
+        error += dx;
+        y += step_y;
+      }
+      if (x, y) != (end_x, end_y) && self.obstacles.contains(&Coord::new(x as usize, y as usize)) {
+        return false;
+      }
+    }
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn model(range: CommunicationRange, relay_max_hops: usize, obstacles: &[(usize, usize)]) -> RangeModel {
+    RangeModel::new(range, OutOfRangePolicy::Drop, relay_max_hops, obstacles.iter().map(|&(x, y)| Coord::new(x, y)).collect())
+  }
+
+  fn positions(robots: &[(char, (usize, usize))]) -> HashMap<char, Coord> {
+    robots.iter().map(|&(id, (x, y))| (id, Coord::new(x, y))).collect()
+  }
+
+  #[test]
+  fn radius_is_euclidean() {
+    let model = model(CommunicationRange::Radius(5), 0, &[]);
+    assert!(model.in_range(Coord::new(0, 0), Coord::new(3, 4)));
+    assert!(!model.in_range(Coord::new(0, 0), Coord::new(4, 4)));
+  }
+
+  #[test]
+  fn obstacles_block_the_line_of_sight() {
+    let model = model(CommunicationRange::LineOfSight(5), 0, &[(2, 0)]);
+    assert!(!model.in_range(Coord::new(0, 0), Coord::new(4, 0)));
+    assert!(model.in_range(Coord::new(0, 1), Coord::new(4, 1)));
+    // The robots' own cells never block
+    assert!(model.in_range(Coord::new(0, 0), Coord::new(2, 0)));
+    // Out of the radius even with a clear line
+    assert!(!model.in_range(Coord::new(0, 1), Coord::new(6, 1)));
+  }
+
+  #[test]
+  fn out_of_range_without_relays_is_unreachable() {
+    let model = model(CommunicationRange::Radius(2), 0, &[]);
+    let positions = positions(&[('A', (0, 0)), ('B', (2, 0)), ('C', (4, 0))]);
+    assert_eq!(model.route('A', 'B', &positions), Some(1));
+    assert_eq!(model.route('A', 'C', &positions), None);
+  }
+
+  #[test]
+  fn relays_bridge_the_gap_up_to_the_hop_limit() {
+    let positions = positions(&[('A', (0, 0)), ('B', (2, 0)), ('C', (4, 0)), ('D', (6, 0))]);
+    let one_hop = model(CommunicationRange::Radius(2), 1, &[]);
+    assert_eq!(one_hop.route('A', 'C', &positions), Some(2));
+    assert_eq!(one_hop.route('A', 'D', &positions), None);
+    let two_hops = model(CommunicationRange::Radius(2), 2, &[]);
+    assert_eq!(two_hops.route('A', 'D', &positions), Some(3));
+  }
+
+  #[test]
+  fn relays_route_around_a_blocked_line_of_sight() {
+    let model = model(CommunicationRange::LineOfSight(3), 1, &[(1, 0)]);
+    let positions = positions(&[('A', (0, 0)), ('B', (2, 0)), ('C', (1, 1))]);
+    assert_eq!(model.route('A', 'B', &positions), Some(2));
+  }
+}
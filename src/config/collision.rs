@@ -0,0 +1,47 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollisionRule {
+    // When a cell cannot take every robot trying to enter it, none of them get in
+    BothBounce,
+    // Robots get in by id, the lowest first, until the cell is full
+    LowerIdFirst,
+    // Carrying groups get in before empty handed robots, then by id
+    CarriersFirst,
+}
+
+// Robots allowed on one cell, None is unlimited. Keep it at least the robots a pickup needs,
+// robots that spawn together or stand still may exceed it
+const CELL_CAPACITY: Option<usize> = None;
+// Robots of a single team allowed on one cell, None is unlimited
+const TEAM_CAPACITY: Option<usize> = None;
+const COLLISION_RULE: CollisionRule = CollisionRule::LowerIdFirst;
+
+pub struct CollisionConfig {
+    pub cell_capacity: Option<usize>,
+    pub team_capacity: Option<usize>,
+    pub rule: CollisionRule,
+}
+
+impl CollisionConfig {
+    pub fn new() -> CollisionConfig {
+        Self {
+            cell_capacity: CELL_CAPACITY,
+            team_capacity: TEAM_CAPACITY,
+            rule: COLLISION_RULE,
+        }
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.cell_capacity.is_some() || self.team_capacity.is_some()
+    }
+
+    // Whether a cell holding `robots` robots, `teammates` of them from the entering robot's team, has room for one more
+    pub fn has_room(&self, robots: usize, teammates: usize) -> bool {
+        self.cell_capacity.is_none_or(|capacity| robots < capacity) && self.team_capacity.is_none_or(|capacity| teammates < capacity)
+    }
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,19 @@
+use crate::robot::consensus::ConsensusKind;
+use crate::robot::Team;
+
+// Protocol each team uses to agree on the pair that picks up the gold
+const RED_CONSENSUS: ConsensusKind = ConsensusKind::Paxos;
+const BLUE_CONSENSUS: ConsensusKind = ConsensusKind::Paxos;
+
+pub struct ConsensusConfig {
+    pub kind: ConsensusKind,
+}
+
+impl ConsensusConfig {
+    pub fn new(team: Team) -> ConsensusConfig {
+        match team {
+            Team::Red => Self { kind: RED_CONSENSUS },
+            Team::Blue => Self { kind: BLUE_CONSENSUS },
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[derive(Clone, Copy, Debug)]
+pub struct OpeningHours {
+    // A box is open for the first `open_turns` turns of every `period` turns
+    pub period: u32,
+    pub open_turns: u32,
+}
+
+impl OpeningHours {
+    pub fn is_open(&self, turn: u32) -> bool {
+        self.period == 0 || turn % self.period < self.open_turns
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BoxRules {
+    // Total value the box accepts, None is unlimited
+    pub capacity: Option<u32>,
+    // None keeps the box open for the whole run
+    pub opening_hours: Option<OpeningHours>,
+}
+
+impl BoxRules {
+    pub fn is_open(&self, turn: u32) -> bool {
+        self.opening_hours.is_none_or(|hours| hours.is_open(turn))
+    }
+
+    pub fn has_room(&self, deposited: u32, value: u8) -> bool {
+        self.capacity.is_none_or(|capacity| deposited + value as u32 <= capacity)
+    }
+}
+
+const BOXES_PER_TEAM: usize = 1;
+// Rules of each team's first, second, ... box, e.g. &[BoxRules { capacity: Some(10), opening_hours: None },
+// BoxRules { capacity: None, opening_hours: Some(OpeningHours { period: 20, open_turns: 10 }) }]
+const BOX_RULES: &[BoxRules] = &[];
+// Rules of the boxes past the end of BOX_RULES
+const DEFAULT_RULES: BoxRules = BoxRules { capacity: None, opening_hours: None };
+
+pub struct DepositConfig {
+    pub boxes_per_team: usize,
+    pub box_rules: &'static [BoxRules],
+    pub default_rules: BoxRules,
+}
+
+impl DepositConfig {
+    pub fn new() -> DepositConfig {
+        Self {
+            boxes_per_team: BOXES_PER_TEAM.max(1),
+            box_rules: BOX_RULES,
+            default_rules: DEFAULT_RULES,
+        }
+    }
+
+    // Rules of a team's box in spawn order
+    pub fn rules(&self, index: usize) -> BoxRules {
+        self.box_rules.get(index).copied().unwrap_or(self.default_rules)
+    }
+}
+
+impl Default for DepositConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
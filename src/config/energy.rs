@@ -0,0 +1,80 @@
+use crate::robot::Action;
+
+const ENERGY: bool = false;
+const BATTERY_CAPACITY: u32 = 200;
+// Energy taken by each action
+const MOVE_COST: u32 = 2;
+const TURN_COST: u32 = 1;
+const PICKUP_COST: u32 = 1;
+const DROP_COST: u32 = 1;
+const IDLE_COST: u32 = 0;
+const JAM_COST: u32 = 3;
+const TACKLE_COST: u32 = 3;
+// Energy taken by every message sent, 0 keeps messaging free
+const MESSAGE_COST: u32 = 0;
+// Added to every move made while carrying a bar
+const CARRYING_EXTRA_COST: u32 = 2;
+// Energy regained each turn spent on one of the team's deposit boxes
+const RECHARGE_RATE: u32 = 20;
+// Robots head back once the battery only covers the trip to the nearest box plus this much
+const RETURN_MARGIN: u32 = 10;
+
+pub struct EnergyConfig {
+    pub enabled: bool,
+    pub capacity: u32,
+    pub move_cost: u32,
+    pub turn_cost: u32,
+    pub pickup_cost: u32,
+    pub drop_cost: u32,
+    pub idle_cost: u32,
+    pub jam_cost: u32,
+    pub tackle_cost: u32,
+    pub message_cost: u32,
+    pub carrying_extra_cost: u32,
+    pub recharge_rate: u32,
+    pub return_margin: u32,
+}
+
+impl EnergyConfig {
+    pub fn new() -> EnergyConfig {
+        Self {
+            enabled: ENERGY,
+            capacity: BATTERY_CAPACITY,
+            move_cost: MOVE_COST,
+            turn_cost: TURN_COST,
+            pickup_cost: PICKUP_COST,
+            drop_cost: DROP_COST,
+            idle_cost: IDLE_COST,
+            jam_cost: JAM_COST,
+            tackle_cost: TACKLE_COST,
+            message_cost: MESSAGE_COST,
+            carrying_extra_cost: CARRYING_EXTRA_COST,
+            recharge_rate: RECHARGE_RATE,
+            return_margin: RETURN_MARGIN,
+        }
+    }
+
+    pub fn action_cost(&self, action: &Action, carrying: bool) -> u32 {
+        match action {
+            Action::Move if carrying => self.move_cost + self.carrying_extra_cost,
+            Action::Move => self.move_cost,
+            Action::Turn(_) => self.turn_cost,
+            Action::PickUp => self.pickup_cost,
+            Action::Drop => self.drop_cost,
+            Action::Idle => self.idle_cost,
+            Action::Jam => self.jam_cost,
+            Action::Tackle => self.tackle_cost,
+        }
+    }
+
+    // Worst case cost of walking `distance` cells, turning at most twice on the way
+    pub fn trip_cost(&self, distance: usize, carrying: bool) -> u32 {
+        distance as u32 * self.action_cost(&Action::Move, carrying) + 2 * self.turn_cost
+    }
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
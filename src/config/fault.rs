@@ -0,0 +1,65 @@
+#[derive(Clone, Copy, Debug)]
+pub enum CrashTurn {
+    At(u32),
+    // Drawn uniformly over the run when the world is created
+    Random,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Crash {
+    pub robot_id: char,
+    pub turn: CrashTurn,
+    // Turns spent down before restarting with an empty knowledge base, None stays down
+    pub restart_after: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ByzantineBehaviour {
+    // Reports made up gold coordinates and amounts in its Simple messages
+    FakeGold,
+    // Promises and accepts every proposal, ignoring earlier promises
+    ConflictingVotes,
+    // Tells the teammates at some known gold to get out every turn
+    SpamGetOut,
+    // Stays put when chosen for the consensus pair
+    RefusePickup,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Byzantine {
+    pub robot_id: char,
+    pub behaviours: &'static [ByzantineBehaviour],
+}
+
+// Crash-stop and crash-recovery failures, e.g. Crash { robot_id: 'A', turn: CrashTurn::At(50), restart_after: Some(20) }
+const CRASHES: &[Crash] = &[];
+
+// Adversarial robots, e.g. Byzantine { robot_id: 'a', behaviours: &[ByzantineBehaviour::FakeGold] }
+const BYZANTINE: &[Byzantine] = &[];
+
+pub struct FaultConfig {
+    pub crashes: &'static [Crash],
+    pub byzantine: &'static [Byzantine],
+}
+
+impl FaultConfig {
+    pub fn new() -> FaultConfig {
+        Self {
+            crashes: CRASHES,
+            byzantine: BYZANTINE,
+        }
+    }
+
+    pub fn byzantine_behaviours(&self, robot_id: char) -> Vec<ByzantineBehaviour> {
+        self.byzantine.iter()
+            .filter(|byzantine| byzantine.robot_id == robot_id)
+            .flat_map(|byzantine| byzantine.behaviours.iter().copied())
+            .collect()
+    }
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
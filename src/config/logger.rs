@@ -5,6 +5,11 @@ const ROBOT_DECISION: bool = true;
 const MESSAGE_BOARD: bool = true;
 const ROBOT_KB: bool = false;
 const ROBOT_MESSAGE: bool = false;
+const KNOWLEDGE_COVERAGE: bool = true;
+const METRICS: bool = true;
+const SAFETY_REPORT: bool = true;
+const PARTITION_REPORT: bool = true;
+const ACTION_OUTCOME: bool = false;
 
 pub struct LoggerConfig {
     pub current_grid: bool,
@@ -14,6 +19,11 @@ pub struct LoggerConfig {
     pub message_board: bool,
     pub robot_kb: bool,
     pub robot_message: bool,
+    pub knowledge_coverage: bool,
+    pub metrics: bool,
+    pub safety_report: bool,
+    pub partition_report: bool,
+    pub action_outcome: bool,
 }
 
 impl LoggerConfig {
@@ -26,6 +36,11 @@ impl LoggerConfig {
             message_board: MESSAGE_BOARD,
             robot_kb: ROBOT_KB,
             robot_message: ROBOT_MESSAGE,
+            knowledge_coverage: KNOWLEDGE_COVERAGE,
+            metrics: METRICS,
+            safety_report: SAFETY_REPORT,
+            partition_report: PARTITION_REPORT,
+            action_outcome: ACTION_OUTCOME,
         }
     }
 }
\ No newline at end of file
@@ -1,4 +1,15 @@
+pub mod collision;
+pub mod consensus;
+pub mod deposit;
+pub mod energy;
+pub mod fault;
 pub mod logger;
+pub mod network;
+pub mod pickup;
+pub mod resolution;
+pub mod spawn;
+pub mod tackle;
+pub mod timeout;
 
 // General Configurations
 const WIDTH: usize = 5;
@@ -8,6 +19,27 @@ const MAX_GOLD: u8 = 5;
 const N_ROBOTS: u8 = 10;
 const TURNS: u32 = 1000;
 const MANUAL: bool = false;
+// Bars are worth 1 to this many points each
+const MAX_BAR_VALUE: u8 = 1;
+
+// Gold Selection: piles are ranked by value per robot required, less this much per enemy last seen on them
+const ENEMY_PENALTY: f64 = 0.5;
+
+// Knowledge Sharing: changed cells are batched into one message per turn, up to MAX_OBSERVATIONS of them
+const KB_SYNC: bool = false;
+const KB_SYNC_MAX_CELLS: usize = 4;
+
+// Message Expiry
+const MESSAGE_TTL: Option<u32> = None;
+
+// Safety Checking
+const SAFETY_CHECKER: bool = true;
+const PANIC_ON_VIOLATION: bool = cfg!(test);
+
+// Paxos Retries
+const MAX_PROPOSAL_RETRIES: u32 = 3;
+const RETRY_BACKOFF_TURNS: u32 = 2;
+
 pub struct Config {
     pub width: usize,
     pub height: usize,
@@ -16,6 +48,15 @@ pub struct Config {
     pub n_robots: u8,
     pub turns: u32,
     pub manual: bool,
+    pub max_bar_value: u8,
+    pub enemy_penalty: f64,
+    pub kb_sync: bool,
+    pub kb_sync_max_cells: usize,
+    pub message_ttl: Option<u32>,
+    pub safety_checker: bool,
+    pub panic_on_violation: bool,
+    pub max_proposal_retries: u32,
+    pub retry_backoff_turns: u32,
 }
 
 impl Config {
@@ -28,6 +69,15 @@ impl Config {
             n_robots: N_ROBOTS,
             turns: TURNS,
             manual: MANUAL,
+            max_bar_value: MAX_BAR_VALUE,
+            enemy_penalty: ENEMY_PENALTY,
+            kb_sync: KB_SYNC,
+            kb_sync_max_cells: KB_SYNC_MAX_CELLS,
+            message_ttl: MESSAGE_TTL,
+            safety_checker: SAFETY_CHECKER,
+            panic_on_violation: PANIC_ON_VIOLATION,
+            max_proposal_retries: MAX_PROPOSAL_RETRIES,
+            retry_backoff_turns: RETRY_BACKOFF_TURNS,
         }
     }
 }
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use crate::communication::message::RetrievalPolicy;
+use crate::communication::partition::{Partition, PartitionSchedule};
+use crate::communication::interference::Interference;
+use crate::communication::auth::MacKey;
+use crate::communication::network::{Bandwidth, DelayDistribution, LinkModel, NetworkModel, OverBudgetPolicy, ReorderPolicy};
+use crate::communication::range::{CommunicationRange, OutOfRangePolicy, RangeModel};
+use crate::robot::Team;
+use crate::util::Coord;
+
+// Cells that block line-of-sight communication
+const COMMUNICATION_OBSTACLES: &[(usize, usize)] = &[];
+
+// Cross-team jamming, messages to or from robots this close to a jammer are lost with this probability
+const JAM_RADIUS: usize = 1;
+const JAM_DROP_PROBABILITY: f64 = 0.5;
+
+// Red Team Network
+const RED_LINK: LinkModel = LinkModel {
+    delay: DelayDistribution::Uniform(0, 3),
+    drop_probability: 0.0,
+    duplication_probability: 0.0,
+    corruption_probability: 0.0,
+};
+const RED_REORDER_POLICY: ReorderPolicy = ReorderPolicy::Preserve;
+const RED_LINK_OVERRIDES: &[(char, char, LinkModel)] = &[];
+const RED_COMMUNICATION_RANGE: CommunicationRange = CommunicationRange::Unlimited;
+const RED_OUT_OF_RANGE_POLICY: OutOfRangePolicy = OutOfRangePolicy::Drop;
+const RED_RELAY_MAX_HOPS: usize = 0;
+const RED_RETRIEVAL_POLICY: RetrievalPolicy = RetrievalPolicy::Random;
+const RED_BANDWIDTH: Bandwidth = Bandwidth {
+    send_budget: None,
+    over_budget_policy: OverBudgetPolicy::Queue,
+    queue_limit: 64,
+};
+// e.g. Partition { from_turn: 100, until_turn: 200, groups: &[&['A', 'B', 'C']] }
+const RED_PARTITIONS: &[Partition] = &[];
+const RED_EAVESDROP_RADIUS: Option<usize> = None;
+const RED_JAMMING: bool = false;
+// Shared key to sign and verify team messages, None sends them unauthenticated
+const RED_AUTH_KEY: Option<MacKey> = None;
+
+// Blue Team Network
+const BLUE_LINK: LinkModel = LinkModel {
+    delay: DelayDistribution::Uniform(0, 3),
+    drop_probability: 0.0,
+    duplication_probability: 0.0,
+    corruption_probability: 0.0,
+};
+const BLUE_REORDER_POLICY: ReorderPolicy = ReorderPolicy::Preserve;
+const BLUE_LINK_OVERRIDES: &[(char, char, LinkModel)] = &[];
+const BLUE_COMMUNICATION_RANGE: CommunicationRange = CommunicationRange::Unlimited;
+const BLUE_OUT_OF_RANGE_POLICY: OutOfRangePolicy = OutOfRangePolicy::Drop;
+const BLUE_RELAY_MAX_HOPS: usize = 0;
+const BLUE_RETRIEVAL_POLICY: RetrievalPolicy = RetrievalPolicy::Random;
+const BLUE_BANDWIDTH: Bandwidth = Bandwidth {
+    send_budget: None,
+    over_budget_policy: OverBudgetPolicy::Queue,
+    queue_limit: 64,
+};
+const BLUE_PARTITIONS: &[Partition] = &[];
+const BLUE_EAVESDROP_RADIUS: Option<usize> = None;
+const BLUE_JAMMING: bool = false;
+const BLUE_AUTH_KEY: Option<MacKey> = None;
+
+pub struct NetworkConfig {
+    pub link: LinkModel,
+    pub reorder_policy: ReorderPolicy,
+    pub link_overrides: &'static [(char, char, LinkModel)],
+    pub communication_range: CommunicationRange,
+    pub out_of_range_policy: OutOfRangePolicy,
+    pub relay_max_hops: usize,
+    pub bandwidth: Bandwidth,
+    pub retrieval_policy: RetrievalPolicy,
+    pub partitions: &'static [Partition],
+    // Overhearing enemy messages within this radius
+    pub eavesdrop_radius: Option<usize>,
+    // Idle robots jam the enemies they see
+    pub jamming: bool,
+    pub auth_key: Option<MacKey>,
+}
+
+impl NetworkConfig {
+    pub fn new(team: Team) -> NetworkConfig {
+        match team {
+            Team::Red => Self {
+                link: RED_LINK,
+                reorder_policy: RED_REORDER_POLICY,
+                link_overrides: RED_LINK_OVERRIDES,
+                communication_range: RED_COMMUNICATION_RANGE,
+                out_of_range_policy: RED_OUT_OF_RANGE_POLICY,
+                relay_max_hops: RED_RELAY_MAX_HOPS,
+                bandwidth: RED_BANDWIDTH,
+                retrieval_policy: RED_RETRIEVAL_POLICY,
+                partitions: RED_PARTITIONS,
+                eavesdrop_radius: RED_EAVESDROP_RADIUS,
+                jamming: RED_JAMMING,
+                auth_key: RED_AUTH_KEY,
+            },
+            Team::Blue => Self {
+                link: BLUE_LINK,
+                reorder_policy: BLUE_REORDER_POLICY,
+                link_overrides: BLUE_LINK_OVERRIDES,
+                communication_range: BLUE_COMMUNICATION_RANGE,
+                out_of_range_policy: BLUE_OUT_OF_RANGE_POLICY,
+                relay_max_hops: BLUE_RELAY_MAX_HOPS,
+                bandwidth: BLUE_BANDWIDTH,
+                retrieval_policy: BLUE_RETRIEVAL_POLICY,
+                partitions: BLUE_PARTITIONS,
+                eavesdrop_radius: BLUE_EAVESDROP_RADIUS,
+                jamming: BLUE_JAMMING,
+                auth_key: BLUE_AUTH_KEY,
+            },
+        }
+    }
+
+    pub fn network_model(&self) -> NetworkModel {
+        let mut network_model = NetworkModel::new(self.link, self.reorder_policy, self.bandwidth);
+        for (sender_id, receiver_id, link) in self.link_overrides {
+            network_model = network_model.with_link_override(*sender_id, *receiver_id, *link);
+        }
+        network_model
+    }
+
+    pub fn partition_schedule(&self) -> PartitionSchedule {
+        PartitionSchedule::new(self.partitions.to_vec())
+    }
+
+    pub fn interference(&self) -> Interference {
+        Interference::new(self.eavesdrop_radius, JAM_RADIUS, JAM_DROP_PROBABILITY)
+    }
+
+    pub fn range_model(&self) -> RangeModel {
+        let obstacles: HashSet<Coord> = COMMUNICATION_OBSTACLES.iter().map(|&(x, y)| Coord::new(x, y)).collect();
+        RangeModel::new(self.communication_range, self.out_of_range_policy, self.relay_max_hops, obstacles)
+    }
+}
@@ -0,0 +1,64 @@
+#[derive(Clone, Copy, Debug)]
+pub enum RobotsRequired {
+    // Every bar needs this many robots
+    Fixed(usize),
+    // Drawn uniformly per cell, inclusive, when the world is created
+    PerCell(usize, usize),
+    // Heavier bars need bigger groups, a bar worth v needs v + 1 robots
+    ByValue,
+}
+
+impl RobotsRequired {
+    // ByValue draws nothing, the cell works it out from its next bar
+    pub fn draw(&self) -> usize {
+        match *self {
+            RobotsRequired::Fixed(robots_required) => robots_required.max(1),
+            RobotsRequired::PerCell(min, max) => rand::random_range(min..=max).max(1),
+            RobotsRequired::ByValue => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroupSize {
+    // Any extra teammate on the cell spoils the pickup
+    Exact,
+    // The required number of robots with the lowest ids pick up, the rest stay empty handed
+    AtLeast,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentionRule {
+    // When both teams can pick up but there are not enough bars for both, nobody picks up
+    NeitherPicks,
+    // The team with more robots on the cell picks up, nobody does on a tie
+    LargerGroup,
+    // A coin flip decides which team picks up
+    Random,
+}
+
+const ROBOTS_REQUIRED: RobotsRequired = RobotsRequired::Fixed(2);
+const GROUP_SIZE: GroupSize = GroupSize::Exact;
+const CONTENTION_RULE: ContentionRule = ContentionRule::NeitherPicks;
+
+pub struct PickupConfig {
+    pub robots_required: RobotsRequired,
+    pub group_size: GroupSize,
+    pub contention_rule: ContentionRule,
+}
+
+impl PickupConfig {
+    pub fn new() -> PickupConfig {
+        Self {
+            robots_required: ROBOTS_REQUIRED,
+            group_size: GROUP_SIZE,
+            contention_rule: CONTENTION_RULE,
+        }
+    }
+}
+
+impl Default for PickupConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
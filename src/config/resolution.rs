@@ -0,0 +1,41 @@
+// How the two teams' turns are put together.
+//
+// With Simultaneous every robot observes the same grid, all actions are submitted before any is
+// applied, and the turn is then resolved in a fixed order:
+//   1. moves and turns are applied, robots may end up sharing a cell
+//   2. jamming and eavesdropping use the positions after the moves
+//   3. tackles reach carriers within range of the tackler's new position
+//   4. pickups are settled per cell with the pickup config's group size and contention rules
+//   5. groups that were tackled, crashed, ran flat or split up fumble their bar
+//   6. groups still carrying on a deposit box score
+// The sequential orders let one team observe, decide and act before the other team observes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TurnOrder {
+    Simultaneous,
+    BlueFirst,
+    RedFirst,
+    // Blue goes first on even turns, Red on odd ones
+    Alternating,
+    // A coin flip every turn decides who goes first
+    Random,
+}
+
+const TURN_ORDER: TurnOrder = TurnOrder::Simultaneous;
+
+pub struct ResolutionConfig {
+    pub turn_order: TurnOrder,
+}
+
+impl ResolutionConfig {
+    pub fn new() -> ResolutionConfig {
+        Self {
+            turn_order: TURN_ORDER,
+        }
+    }
+}
+
+impl Default for ResolutionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
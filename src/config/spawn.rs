@@ -0,0 +1,43 @@
+#[derive(Clone, Copy, Debug)]
+pub struct Hotspot {
+    pub x: usize,
+    pub y: usize,
+    pub radius: usize,
+    // Chance per turn that a bar appears somewhere within the radius
+    pub probability: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum SpawnProcess {
+    // Gold only exists at world creation
+    Disabled,
+    // Every cell gains a bar with this probability each turn
+    Uniform(f64),
+    Hotspots(&'static [Hotspot]),
+    // Every `period` turns `bars` bars appear at random cells, announced `warning` turns ahead
+    Waves { period: u32, bars: u8, warning: u32 },
+}
+
+const GOLD_SPAWN: SpawnProcess = SpawnProcess::Disabled;
+// Spawns never push a cell above this many bars
+const MAX_SPAWNED_GOLD: u8 = 5;
+
+pub struct SpawnConfig {
+    pub process: SpawnProcess,
+    pub max_gold: u8,
+}
+
+impl SpawnConfig {
+    pub fn new() -> SpawnConfig {
+        Self {
+            process: GOLD_SPAWN,
+            max_gold: MAX_SPAWNED_GOLD,
+        }
+    }
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
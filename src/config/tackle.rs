@@ -0,0 +1,36 @@
+use crate::robot::Team;
+use crate::util::Coord;
+
+// Enemy carriers within this many cells (Manhattan) can be tackled, 0 only on the same cell
+const TACKLE_RANGE: usize = 1;
+const TACKLE_SUCCESS_PROBABILITY: f64 = 0.5;
+// Turns a robot waits after a tackle before it can tackle again
+const TACKLE_COOLDOWN: u32 = 5;
+
+const RED_TACKLING: bool = false;
+const BLUE_TACKLING: bool = false;
+
+pub struct TackleConfig {
+    pub enabled: bool,
+    pub range: usize,
+    pub success_probability: f64,
+    pub cooldown: u32,
+}
+
+impl TackleConfig {
+    pub fn new(team: Team) -> TackleConfig {
+        Self {
+            enabled: match team {
+                Team::Red => RED_TACKLING,
+                Team::Blue => BLUE_TACKLING,
+            },
+            range: TACKLE_RANGE,
+            success_probability: TACKLE_SUCCESS_PROBABILITY,
+            cooldown: TACKLE_COOLDOWN,
+        }
+    }
+
+    pub fn in_range(&self, tackler: Coord, carrier: Coord) -> bool {
+        tackler.x.abs_diff(carrier.x) + tackler.y.abs_diff(carrier.y) <= self.range
+    }
+}
@@ -0,0 +1,33 @@
+// Turns a robot waits in a state without progress before recovering, None waits forever
+const CLUSTER_FINDING_TIMEOUT: Option<usize> = Some(20);
+const PAXOS_TIMEOUT: Option<usize> = Some(30);
+const DIRECTION_ACK_TIMEOUT: Option<usize> = Some(10);
+const TASK_COMPLETION_TIMEOUT: Option<usize> = Some(60);
+
+pub struct TimeoutConfig {
+    // Waiting for every teammate's gold report
+    pub cluster_finding: Option<usize>,
+    // Waiting for majority promises or a confirmed value
+    pub paxos: Option<usize>,
+    // Waiting for the partner to acknowledge the pickup direction
+    pub direction_ack: Option<usize>,
+    // Waiting for the chosen pair to deliver their gold
+    pub task_completion: Option<usize>,
+}
+
+impl TimeoutConfig {
+    pub fn new() -> TimeoutConfig {
+        Self {
+            cluster_finding: CLUSTER_FINDING_TIMEOUT,
+            paxos: PAXOS_TIMEOUT,
+            direction_ack: DIRECTION_ACK_TIMEOUT,
+            task_completion: TASK_COMPLETION_TIMEOUT,
+        }
+    }
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,11 +2,17 @@ use std::fmt::{Debug, Formatter};
 use crate::robot::{Robot, Team};
 use colored::Colorize;
 use crate::util::Coord;
+use crate::config::pickup::RobotsRequired;
+use crate::config::Config;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 enum CellContent {
-    GoldBars(u8),
-    DepositBox(Team, u8),
+    // Value of every bar on the cell, the last one is picked up first
+    GoldBars(Vec<u8>),
+    // Gold only known from someone else's report, which gives the bar count and their total value
+    ReportedGold(u8, u16),
+    // Team and value deposited so far
+    DepositBox(Team, u32),
 }
 
 #[derive(Clone)]
@@ -16,15 +22,20 @@ pub struct Cell {
     pub red_robots_ids: Vec<char>,
     pub blue_robots: u8,
     pub blue_robots_ids: Vec<char>,
+    // Robots on the cell that carry a bar, visible to anyone observing it
+    pub carrying_ids: Vec<char>,
     pub content: Option<CellContent>,
+    // Robots of one team needed to pick up a bar here, 0 when it depends on the bar values or is only known from a report
+    pub robots_required: usize,
 }
 
 // Constructor
 impl Cell {
-    pub fn new(coord: (usize, usize), p_gold: f64, max_gold: u8) -> Self {
+    pub fn new(coord: (usize, usize), p_gold: f64, max_gold: u8, robots_required: RobotsRequired) -> Self {
         let contain_gold = rand::random_bool(p_gold);
         let content = if contain_gold {
-            Some(CellContent::GoldBars(rand::random_range(1..=max_gold)))
+            let gold_amount = rand::random_range(1..=max_gold);
+            Some(CellContent::GoldBars((0..gold_amount).map(|_| Self::random_bar_value()).collect()))
         } else {
             None
         };
@@ -34,7 +45,22 @@ impl Cell {
             red_robots_ids: Vec::new(),
             blue_robots: 0,
             blue_robots_ids: Vec::new(),
+            carrying_ids: Vec::new(),
             content,
+            robots_required: robots_required.draw(),
+        }
+    }
+
+    pub fn from_observation(coord: Coord, gold: Option<(u8, u16)>, red_robots: u8, blue_robots: u8) -> Self {
+        Cell {
+            coord,
+            red_robots,
+            red_robots_ids: Vec::new(),
+            blue_robots,
+            blue_robots_ids: Vec::new(),
+            carrying_ids: Vec::new(),
+            content: gold.map(|(bars, value)| CellContent::ReportedGold(bars, value)),
+            robots_required: 0,
         }
     }
 }
@@ -76,28 +102,64 @@ impl Cell {
 
 // Gold logic
 impl Cell {
+    pub fn random_bar_value() -> u8 {
+        let Config { max_bar_value, .. } = Config::new();
+        rand::random_range(1..=max_bar_value)
+    }
+
+    // Number of bars
     pub fn get_gold_amount(&self) -> Option<u8> {
-        match self.content {
-            Some(CellContent::GoldBars(n)) => Some(n),
+        match &self.content {
+            Some(CellContent::GoldBars(bars)) => Some(bars.len() as u8),
+            Some(CellContent::ReportedGold(bars, _)) => Some(*bars),
+            _ => None,
+        }
+    }
+
+    // Total value of the bars
+    pub fn get_gold_value(&self) -> Option<u16> {
+        match &self.content {
+            Some(CellContent::GoldBars(bars)) => Some(bars.iter().map(|value| *value as u16).sum()),
+            Some(CellContent::ReportedGold(_, value)) => Some(*value),
             _ => None,
         }
     }
 
-    pub fn remove_gold(&mut self) {
-        match self.content {
-            Some(CellContent::GoldBars(n)) if n > 1 => self.content = Some(CellContent::GoldBars(n - 1)),
-            Some(CellContent::GoldBars(_)) => self.content = None,
-            _ => ()
+    // Robots of one team needed to lift the next bar, reported gold only gives an estimate:
+    // the average bar for ByValue and the smallest group PerCell can draw
+    pub fn get_robots_required(&self, robots_required: RobotsRequired) -> usize {
+        match (robots_required, &self.content) {
+            (RobotsRequired::ByValue, Some(CellContent::GoldBars(bars))) => *bars.last().unwrap() as usize + 1,
+            (RobotsRequired::ByValue, Some(CellContent::ReportedGold(bars, value))) => value.div_ceil((*bars).max(1) as u16) as usize + 1,
+            (RobotsRequired::Fixed(robots_required), Some(CellContent::ReportedGold(_, _))) => robots_required.max(1),
+            (RobotsRequired::PerCell(min, _), Some(CellContent::ReportedGold(_, _))) => min.max(1),
+            _ => self.robots_required,
         }
     }
 
-    pub fn add_gold(&mut self) {
-        match self.content {
-            Some(CellContent::GoldBars(n)) => self.content = Some(CellContent::GoldBars(n + 1)),
-            Some(CellContent::DepositBox(team, n)) => {
-                self.content = Some(CellContent::DepositBox(team, n + 1));
+    // Returns the value of the bar taken
+    pub fn remove_gold(&mut self) -> Option<u8> {
+        match &mut self.content {
+            Some(CellContent::GoldBars(bars)) => {
+                let value = bars.pop();
+                if bars.is_empty() {
+                    self.content = None;
+                }
+                value
+            },
+            _ => None,
+        }
+    }
+
+    pub fn add_gold(&mut self, value: u8) {
+        match &mut self.content {
+            Some(CellContent::GoldBars(bars)) => bars.push(value),
+            Some(CellContent::ReportedGold(bars, total)) => {
+                *bars = bars.saturating_add(1);
+                *total = total.saturating_add(value as u16);
             },
-            None => self.content = Some(CellContent::GoldBars(1))
+            Some(CellContent::DepositBox(_, deposited)) => *deposited += value as u32,
+            None => self.content = Some(CellContent::GoldBars(vec![value])),
         }
     }
 }
@@ -116,12 +178,17 @@ impl Cell {
         }
     }
 
-    pub fn increment_score(&mut self) {
-        match self.content {
-            Some(CellContent::DepositBox(team, n)) => {
-                self.content = Some(CellContent::DepositBox(team, n + 1));
-            },
-            _ => ()
+    pub fn get_deposited(&self) -> Option<u32> {
+        if let Some(CellContent::DepositBox(_, deposited)) = &self.content {
+            Some(*deposited)
+        } else {
+            None
+        }
+    }
+
+    pub fn increment_score(&mut self, value: u8) {
+        if let Some(CellContent::DepositBox(_, deposited)) = &mut self.content {
+            *deposited += value as u32;
         }
     }
 }
@@ -130,7 +197,8 @@ impl Cell {
 impl Debug for Cell {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let content = match &self.content {
-            Some(CellContent::GoldBars(n)) => format!(" {} ", n).bright_yellow().italic(),
+            Some(CellContent::GoldBars(bars)) => format!(" {} ", bars.len()).bright_yellow().italic(),
+            Some(CellContent::ReportedGold(bars, _)) => format!(" {} ", bars).yellow().italic(),
             Some(CellContent::DepositBox(Team::Red, n)) => format!("[{}]", n).to_string().red().bold(),
             Some(CellContent::DepositBox(Team::Blue, n)) => format!("[{}]", n).to_string().blue().bold(),
             None => "   ".to_string().green(),
@@ -1,70 +1,105 @@
 pub mod cell;
 pub mod grid;
+pub mod spawn;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use cell::Cell;
 use crate::environment::grid::Grid;
+use crate::environment::spawn::GoldSpawner;
 use crate::util::Coord;
-use crate::robot::{Action, Team};
+use crate::robot::{Action, ActionOutcome, Team};
 use crate::robot::Direction::{Left, Right, Up, Down};
 use crate::robot::Robot;
 use colored::Colorize;
 use crate::communication::message::{MessageBoard, MessageBox};
 use crate::config::logger::LoggerConfig;
+use crate::config::network::NetworkConfig;
+use crate::config::Config;
+use crate::communication::checker::SafetyChecker;
+use crate::config::consensus::ConsensusConfig;
+use crate::config::fault::{CrashTurn, FaultConfig};
+use crate::config::deposit::{BoxRules, DepositConfig};
+use crate::config::tackle::TackleConfig;
+use crate::config::resolution::{ResolutionConfig, TurnOrder};
+use crate::config::collision::{CollisionConfig, CollisionRule};
+use crate::config::pickup::{ContentionRule, GroupSize, PickupConfig};
 use crate::robot::manager::{RobotManager};
 
+// A crash from the fault configuration with its turns resolved
+struct ScheduledCrash {
+    robot_id: char,
+    crash_turn: u32,
+    restart_turn: Option<u32>,
+}
+
 pub struct World {
     manual: bool,
     grid: Grid,
     width: usize,
     height: usize,
-    red_score: u8,
-    blue_score: u8,
-    red_deposit_box: Coord,
-    blue_deposit_box: Coord,
+    red_score: u32,
+    blue_score: u32,
+    red_deposit_boxes: Vec<Coord>,
+    blue_deposit_boxes: Vec<Coord>,
+    // Capacity and opening hours of every box of both teams
+    deposit_rules: HashMap<Coord, BoxRules>,
     pick_up_check: HashMap<Coord, Vec<(char, Team)>>,
     red_team: RobotManager,
     blue_team: RobotManager,
+    turn: u32,
+    crash_schedule: Vec<ScheduledCrash>,
+    gold_spawner: GoldSpawner,
+    pickup_config: PickupConfig,
+    collision_config: CollisionConfig,
+    resolution_config: ResolutionConfig,
     
     logger_config: LoggerConfig,
 
-    pub total_gold_amount: u8,
+    pub total_gold_amount: u32,
 }
 
 // Constructor and Getters
 impl World {
     pub fn new(width: usize, height: usize, p_gold: f64, max_gold: u8, n_robots: u8, manual: bool) -> Self {
+        let pickup_config = PickupConfig::new();
         let mut grid: Vec<Vec<Cell>> = Vec::new();
         let mut total_gold_amount = 0;
         for y in (0..height).rev() {
             let mut row: Vec<Cell> = Vec::new();
             for x in 0..width {
-                let new_cell = Cell::new((x, y), p_gold, max_gold);
+                let new_cell = Cell::new((x, y), p_gold, max_gold, pickup_config.robots_required);
                 if new_cell.get_gold_amount().is_some() {
-                    total_gold_amount += new_cell.get_gold_amount().unwrap();
+                    total_gold_amount += new_cell.get_gold_amount().unwrap() as u32;
                 }
                 row.push(new_cell);
             }
             grid.push(row);
         }
         let mut grid = Grid::new(grid, width, height);
-        let (red_deposit_box, blue_deposit_box, gold_to_be_removed) = Self::spawn_deposit_box(width, height, &mut grid);
+        let (red_deposit_boxes, blue_deposit_boxes, deposit_rules, gold_to_be_removed) = Self::spawn_deposit_boxes(width, height, &mut grid);
         total_gold_amount -= gold_to_be_removed;
-        let (blue_team, blue_message_board) = Self::spawn_robots(width, height, &mut grid, n_robots, Team::Blue, blue_deposit_box);
-        let (red_team, red_message_board) = Self::spawn_robots(width, height, &mut grid, n_robots, Team::Red, red_deposit_box);
+        let (blue_team, blue_message_board) = Self::spawn_robots(width, height, &mut grid, n_robots, Team::Blue, &blue_deposit_boxes, &deposit_rules);
+        let (red_team, red_message_board) = Self::spawn_robots(width, height, &mut grid, n_robots, Team::Red, &red_deposit_boxes, &deposit_rules);
         Self {
             manual,
             grid,
             width,
             height,
-            red_deposit_box,
-            blue_deposit_box,
+            red_deposit_boxes,
+            blue_deposit_boxes,
+            deposit_rules,
             red_score: 0,
             blue_score: 0,
             pick_up_check: HashMap::new(),
             red_team: RobotManager::new(Team::Red, red_team, red_message_board),
             blue_team: RobotManager::new(Team::Blue, blue_team, blue_message_board),
+            turn: 0,
+            crash_schedule: Self::schedule_crashes(),
+            gold_spawner: GoldSpawner::new(width, height),
+            pickup_config,
+            collision_config: CollisionConfig::new(),
+            resolution_config: ResolutionConfig::new(),
             logger_config: LoggerConfig::new(),
             total_gold_amount,
         }
@@ -82,19 +117,19 @@ impl World {
         self.height
     }
 
-    pub fn get_red_deposit_box(&self) -> &Coord {
-        &self.red_deposit_box
+    pub fn get_red_deposit_boxes(&self) -> &Vec<Coord> {
+        &self.red_deposit_boxes
     }
 
-    pub fn get_blue_deposit_box(&self) -> &Coord {
-        &self.blue_deposit_box
+    pub fn get_blue_deposit_boxes(&self) -> &Vec<Coord> {
+        &self.blue_deposit_boxes
     }
 
-    pub fn get_red_score(&self) -> u8 {
+    pub fn get_red_score(&self) -> u32 {
         self.red_score
     }
 
-    pub fn get_blue_score(&self) -> u8 {
+    pub fn get_blue_score(&self) -> u32 {
         self.blue_score
     }
 
@@ -110,30 +145,61 @@ impl World {
 
 // Initialization functions
 impl World {
-    fn spawn_deposit_box(width: usize, height: usize, grid: &mut Grid) -> (Coord, Coord, u8) {
+    // Every team gets its boxes on distinct cells, returns their rules and the gold that was lying under them
+    fn spawn_deposit_boxes(width: usize, height: usize, grid: &mut Grid) -> (Vec<Coord>, Vec<Coord>, HashMap<Coord, BoxRules>, u32) {
+        let deposit_config = DepositConfig::new();
+        let boxes_per_team = deposit_config.boxes_per_team.min(width * height / 2);
+        let mut deposit_rules = HashMap::new();
         let mut gold_to_be_remove = 0;
-        let red_deposit_box = Coord::random(0..width, 0..height);
-        let mut blue_deposit_box: Coord;
-        loop {
-            blue_deposit_box = Coord::random(0..width, 0..height);
-            if blue_deposit_box != red_deposit_box {
-                break;
+        let mut taken: Vec<Coord> = Vec::new();
+        let mut red_deposit_boxes = Vec::new();
+        let mut blue_deposit_boxes = Vec::new();
+        for team in [Team::Red, Team::Blue] {
+            for index in 0..boxes_per_team {
+                let mut deposit_box: Coord;
+                loop {
+                    deposit_box = Coord::random(0..width, 0..height);
+                    if !taken.contains(&deposit_box) {
+                        break;
+                    }
+                }
+                taken.push(deposit_box);
+                deposit_rules.insert(deposit_box, deposit_config.rules(index));
+                let cell = grid.get_mut_cell(deposit_box).unwrap();
+                gold_to_be_remove += cell.get_gold_amount().unwrap_or(0) as u32;
+                cell.set_deposit_box(team);
+                match team {
+                    Team::Red => red_deposit_boxes.push(deposit_box),
+                    Team::Blue => blue_deposit_boxes.push(deposit_box),
+                }
             }
         }
-        if grid.get_cell(red_deposit_box).unwrap().get_gold_amount().is_some() {
-            gold_to_be_remove += grid.get_cell(red_deposit_box).unwrap().get_gold_amount().unwrap();
-        }
-        if grid.get_cell(blue_deposit_box).unwrap().get_gold_amount().is_some() {
-            gold_to_be_remove += grid.get_cell(blue_deposit_box).unwrap().get_gold_amount().unwrap();
-        }
-        grid.get_mut_cell(red_deposit_box).unwrap().set_deposit_box(Team::Red);
-        grid.get_mut_cell(blue_deposit_box).unwrap().set_deposit_box(Team::Blue);
-        (red_deposit_box, blue_deposit_box, gold_to_be_remove)
+        (red_deposit_boxes, blue_deposit_boxes, deposit_rules, gold_to_be_remove)
     }
 
-    fn spawn_robots(width: usize, height: usize, grid: &mut Grid, n_robots: u8, team: Team, deposit_box: Coord) -> (HashMap<char, Robot>, Arc<Mutex<MessageBoard>>) {
+    fn schedule_crashes() -> Vec<ScheduledCrash> {
+        let Config { turns, .. } = Config::new();
+        FaultConfig::new().crashes.iter().map(|crash| {
+            let crash_turn = match crash.turn {
+                CrashTurn::At(turn) => turn,
+                CrashTurn::Random => rand::random_range(0..turns),
+            };
+            ScheduledCrash {
+                robot_id: crash.robot_id,
+                crash_turn,
+                restart_turn: crash.restart_after.map(|restart_after| crash_turn + restart_after),
+            }
+        }).collect()
+    }
+
+    fn spawn_robots(width: usize, height: usize, grid: &mut Grid, n_robots: u8, team: Team, deposit_boxes: &[Coord], deposit_rules: &HashMap<Coord, BoxRules>) -> (HashMap<char, Robot>, Arc<Mutex<MessageBoard>>) {
+        let team_rules: HashMap<Coord, BoxRules> = deposit_boxes.iter().map(|deposit_box| (*deposit_box, deposit_rules[deposit_box])).collect();
         let mut robots: HashMap<char, Robot> = HashMap::new();
-        let message_board: Arc<Mutex<MessageBoard>> = Arc::new(Mutex::new(MessageBoard::new()));
+        let network_config = NetworkConfig::new(team);
+        let Config { safety_checker, panic_on_violation, .. } = Config::new();
+        let consensus_config = ConsensusConfig::new(team);
+        let checker = if safety_checker { Some(SafetyChecker::new(panic_on_violation, consensus_config.kind.requires_majority())) } else { None };
+        let message_board: Arc<Mutex<MessageBoard>> = Arc::new(Mutex::new(MessageBoard::new(network_config.network_model(), network_config.range_model(), network_config.retrieval_policy, checker, network_config.partition_schedule(), network_config.interference())));
         let first_id = match team {
             Team::Red => b'A',
             Team::Blue => b'a',
@@ -142,13 +208,14 @@ impl World {
             let id = (first_id + i) as char;
             message_board.lock().unwrap().insert(id, MessageBox::new());
             let current_pos = Coord::random(0..width, 0..height);
+            message_board.lock().unwrap().set_position(id, current_pos);
             let facing = match rand::random_range(0..4) {
                 0 => Left,
                 1 => Right,
                 2 => Down,
                 _ => Up,
             };
-            let new_robot = Robot::new(id, team, current_pos, facing, Arc::clone(&message_board), deposit_box);
+            let new_robot = Robot::new(id, team, current_pos, facing, Arc::clone(&message_board), deposit_boxes.to_vec(), team_rules.clone());
             grid.get_mut_cell(current_pos).unwrap().add_bot(&new_robot);
             robots.insert(id, new_robot);
         }
@@ -160,21 +227,49 @@ impl World {
 impl World {
 
     pub fn next_turn(&mut self) {
-        self.make_decision(Team::Blue);
-        println!();
-        self.make_decision(Team::Red);
-
-        println!();
+        self.inject_faults();
+        self.total_gold_amount += self.gold_spawner.spawn(self.turn, &mut self.grid);
+        self.blue_team.update_positions();
+        self.red_team.update_positions();
+        self.mark_carriers();
 
         self.pick_up_check.clear();
-        self.take_actions(Team::Blue);
-        println!();
-        self.take_actions(Team::Red);
+        match self.team_order() {
+            None => {
+                self.make_decision(Team::Blue);
+                println!();
+                self.make_decision(Team::Red);
+
+                println!();
+
+                let mut actions = self.submit_actions(Team::Blue);
+                actions.extend(self.submit_actions(Team::Red));
+                self.take_actions(actions);
+            },
+            Some(teams) => {
+                for team in teams {
+                    self.make_decision(team);
+                    println!();
+                    let actions = self.submit_actions(team);
+                    self.take_actions(actions);
+                    println!();
+                }
+            },
+        }
+
+        self.exchange_interference();
+        self.resolve_tackles();
 
         self.check_pickup_logic();
         self.check_fumble();
         self.check_drop_deposit();
 
+        if self.logger_config.action_outcome {
+            println!();
+            self.blue_team.print_outcomes();
+            self.red_team.print_outcomes();
+        }
+
         // println!();
         // self.blue_team.print_message_board_debug();
 
@@ -187,6 +282,7 @@ impl World {
             self.red_team.print_message_board();
         }
 
+        self.turn += 1;
     }
     pub fn make_decision(&mut self, team: Team) {
         if (self.logger_config.robot_observation) {
@@ -200,6 +296,9 @@ impl World {
             Team::Blue => &mut self.blue_team,
         };
         for robot in robot_manager.get_robots() {
+            if robot.is_crashed() || robot.is_flat() {
+                continue;
+            }
             let observations = robot.observable_cells(self.width, self.height);
             robot.observe(&mut self.grid);
             if (self.logger_config.robot_observation) {
@@ -211,7 +310,24 @@ impl World {
         }
     }
 
-    pub fn take_actions(&mut self, team: Team) {
+    // None when both teams act simultaneously, otherwise the order the teams take their turns in
+    fn team_order(&self) -> Option<[Team; 2]> {
+        let blue_first = match self.resolution_config.turn_order {
+            TurnOrder::Simultaneous => return None,
+            TurnOrder::BlueFirst => true,
+            TurnOrder::RedFirst => false,
+            TurnOrder::Alternating => self.turn % 2 == 0,
+            TurnOrder::Random => rand::random_bool(0.5),
+        };
+        if blue_first {
+            Some([Team::Blue, Team::Red])
+        } else {
+            Some([Team::Red, Team::Blue])
+        }
+    }
+
+    // Every robot of the team decides without any action being applied yet
+    pub fn submit_actions(&mut self, team: Team) -> Vec<(Team, char, Action)> {
         if (self.logger_config.robot_decision) {
             match team {
                 Team::Red => println!("{}{:?} {}", "|".red(), team, "Robots Decisions".bold()),
@@ -222,7 +338,12 @@ impl World {
             Team::Red => &mut self.red_team,
             Team::Blue => &mut self.blue_team,
         };
+        let mut actions = Vec::new();
         for robot in robot_manager.get_robots() {
+            if robot.is_crashed() || robot.is_flat() {
+                actions.push((team, robot.get_id(), Action::Idle));
+                continue;
+            }
             let action = robot.make_decision(self.manual);
             if let Action::PickUp = action {
                 self.pick_up_check.entry(robot.get_coord()).or_insert(Vec::new()).push((robot.get_id(), team));
@@ -233,9 +354,214 @@ impl World {
                     Team::Blue => println!("{}{:?} Robot {:?} decided to {:?}", "|".blue(), team, robot, action)
                 }
             }
-            robot.take_action(&action, &mut self.grid);
+            actions.push((team, robot.get_id(), action));
+        }
+        actions
+    }
+
+    // Moves into full cells are turned back first, everything else is applied as submitted
+    pub fn take_actions(&mut self, actions: Vec<(Team, char, Action)>) {
+        let bounced = self.resolve_collisions(&actions);
+        for (team, id, action) in actions {
+            let robot = match team {
+                Team::Red => self.red_team.get_robot_by_id(id).unwrap(),
+                Team::Blue => self.blue_team.get_robot_by_id(id).unwrap(),
+            };
+            if bounced.contains(&id) {
+                println!("Robot {} {} off {:?}", team.style(id.to_string()), "BOUNCED".yellow().bold(), robot.next_coord(self.width, self.height).unwrap());
+                robot.bounce();
+            } else {
+                robot.take_action(&action, &mut self.grid);
+            }
+        }
+    }
+}
+
+struct Mover {
+    id: char,
+    team: Team,
+    from: Coord,
+    to: Coord,
+    carrying: bool,
+    carrying_group: Vec<char>,
+}
+
+// Collision Logic
+impl World {
+    // Returns the robots whose move is turned back. Every cell admits the robots entering it in the
+    // collision rule's order while it has room, counting the robots that stay put. Turned back robots
+    // stay put in turn and take their whole carrying group with them, so cells are rechecked until
+    // nothing changes. Robots moving off the grid edge are left to take_action.
+    fn resolve_collisions(&mut self, actions: &[(Team, char, Action)]) -> HashSet<char> {
+        let mut bounced: HashSet<char> = HashSet::new();
+        if !self.collision_config.is_limited() {
+            return bounced;
+        }
+        let mut movers: Vec<Mover> = Vec::new();
+        for (team, id, action) in actions {
+            if *action != Action::Move {
+                continue;
+            }
+            let robot = match team {
+                Team::Red => self.red_team.get_robot_by_id(*id).unwrap(),
+                Team::Blue => self.blue_team.get_robot_by_id(*id).unwrap(),
+            };
+            if let Some(to) = robot.next_coord(self.width, self.height) {
+                movers.push(Mover { id: *id, team: *team, from: robot.get_coord(), to, carrying: robot.is_carrying(), carrying_group: robot.get_carrying_group() });
+            }
+        }
+        match self.collision_config.rule {
+            CollisionRule::BothBounce | CollisionRule::LowerIdFirst => movers.sort_by_key(|mover| mover.id),
+            CollisionRule::CarriersFirst => movers.sort_by_key(|mover| (!mover.carrying, mover.id)),
+        }
+        // Robots on each cell before anyone moves, by team
+        let mut start: HashMap<Coord, (usize, usize)> = HashMap::new();
+        for mover in movers.iter() {
+            for coord in [mover.from, mover.to] {
+                let cell = self.grid.get_cell(coord).unwrap();
+                start.insert(coord, (cell.red_robots as usize, cell.blue_robots as usize));
+            }
+        }
+        loop {
+            let mut occupancy = start.clone();
+            for mover in movers.iter().filter(|mover| !bounced.contains(&mover.id)) {
+                let (red, blue) = occupancy.get_mut(&mover.from).unwrap();
+                match mover.team {
+                    Team::Red => *red -= 1,
+                    Team::Blue => *blue -= 1,
+                }
+            }
+            let mut turned_back: Vec<char> = Vec::new();
+            let destinations: HashSet<Coord> = movers.iter().filter(|mover| !bounced.contains(&mover.id)).map(|mover| mover.to).collect();
+            for destination in destinations {
+                let entering: Vec<&Mover> = movers.iter().filter(|mover| mover.to == destination && !bounced.contains(&mover.id)).collect();
+                let (mut red, mut blue) = occupancy[&destination];
+                let mut refused = Vec::new();
+                for mover in entering.iter() {
+                    let teammates = match mover.team {
+                        Team::Red => red,
+                        Team::Blue => blue,
+                    };
+                    if self.collision_config.has_room(red + blue, teammates) {
+                        match mover.team {
+                            Team::Red => red += 1,
+                            Team::Blue => blue += 1,
+                        }
+                    } else {
+                        refused.push(mover.id);
+                    }
+                }
+                if self.collision_config.rule == CollisionRule::BothBounce && !refused.is_empty() {
+                    refused = entering.iter().map(|mover| mover.id).collect();
+                }
+                turned_back.extend(refused);
+            }
+            // Carrying groups move as one
+            for mover in movers.iter() {
+                if turned_back.contains(&mover.id) || bounced.contains(&mover.id) {
+                    continue;
+                }
+                if mover.carrying_group.iter().any(|member| turned_back.contains(member) || bounced.contains(member)) {
+                    turned_back.push(mover.id);
+                }
+            }
+            if turned_back.is_empty() {
+                return bounced;
+            }
+            bounced.extend(turned_back);
+        }
+    }
+}
+
+// Fault logic
+impl World {
+    fn inject_faults(&mut self) {
+        for crash in self.crash_schedule.iter() {
+            let robot = match self.red_team.get_robot_by_id(crash.robot_id) {
+                Some(robot) => robot,
+                None => match self.blue_team.get_robot_by_id(crash.robot_id) {
+                    Some(robot) => robot,
+                    None => continue,
+                },
+            };
+            if crash.crash_turn == self.turn && !robot.is_crashed() {
+                robot.crash();
+            } else if crash.restart_turn == Some(self.turn) && robot.is_crashed() {
+                robot.restart();
+            }
         }
+    }
+}
 
+// Cross-team logic
+impl World {
+    fn exchange_interference(&mut self) {
+        let red_broadcasts = self.red_team.take_broadcasts();
+        let blue_broadcasts = self.blue_team.take_broadcasts();
+        self.red_team.overhear(blue_broadcasts);
+        self.blue_team.overhear(red_broadcasts);
+        let red_jammers = self.red_team.get_jammer_coords();
+        let blue_jammers = self.blue_team.get_jammer_coords();
+        self.red_team.set_jammers(blue_jammers);
+        self.blue_team.set_jammers(red_jammers);
+    }
+}
+
+// Tackle Logic
+impl World {
+    // Carriers are visible to every robot observing their cell
+    fn mark_carriers(&mut self) {
+        for x in 0..self.width {
+            for y in 0..self.height {
+                self.grid.get_mut_cell(Coord::new(x, y)).unwrap().carrying_ids.clear();
+            }
+        }
+        for carrier in self.red_team.get_robots().into_iter().chain(self.blue_team.get_robots()) {
+            if carrier.is_carrying() {
+                self.grid.get_mut_cell(carrier.get_coord()).unwrap().carrying_ids.push(carrier.get_id());
+            }
+        }
+    }
+
+    fn resolve_tackles(&mut self) {
+        let red_tacklers = self.red_team.get_tacklers();
+        let blue_tacklers = self.blue_team.get_tacklers();
+        let red_landed = Self::tackle_groups(self.blue_team.get_carrying_robot(), &red_tacklers, &TackleConfig::new(Team::Red));
+        let blue_landed = Self::tackle_groups(self.red_team.get_carrying_robot(), &blue_tacklers, &TackleConfig::new(Team::Blue));
+        self.red_team.report_outcome(&red_landed, ActionOutcome::TackleLanded);
+        self.blue_team.report_outcome(&blue_landed, ActionOutcome::TackleLanded);
+        self.red_team.record_tackles(red_tacklers.len() as u32, red_landed.len() as u32);
+        self.blue_team.record_tackles(blue_tacklers.len() as u32, blue_landed.len() as u32);
+    }
+
+    // Each tackler goes for the closest group in range that has not been knocked yet, returns the tacklers that landed
+    fn tackle_groups(carriers: Option<Vec<&mut Robot>>, tacklers: &[(char, Coord)], tackle_config: &TackleConfig) -> Vec<char> {
+        let mut groups = Self::carrying_groups(carriers);
+        let mut landed = Vec::new();
+        for (tackler_id, tackler_coord) in tacklers {
+            let distance = |group: &Vec<&mut Robot>| group.iter()
+                .map(|carrier| carrier.get_coord().x.abs_diff(tackler_coord.x) + carrier.get_coord().y.abs_diff(tackler_coord.y))
+                .min()
+                .unwrap();
+            let target = groups.iter_mut()
+                .filter(|group| !group[0].is_tackled() && group.iter().any(|carrier| tackle_config.in_range(*tackler_coord, carrier.get_coord())))
+                .min_by_key(|group| distance(group));
+            let Some(group) = target else {
+                println!("Robot {} tackled but found no carrier in range", tackler_id);
+                continue;
+            };
+            let ids: Vec<char> = group.iter().map(|carrier| carrier.get_id()).collect();
+            if rand::random_bool(tackle_config.success_probability) {
+                println!("Robot {} {} {:?}", tackler_id, "TACKLED".on_magenta().bold(), ids);
+                for carrier in group.iter_mut() {
+                    carrier.tackle();
+                }
+                landed.push(*tackler_id);
+            } else {
+                println!("Robot {} missed a tackle on {:?}", tackler_id, ids);
+            }
+        }
+        landed
     }
 }
 
@@ -243,102 +569,162 @@ impl World {
 impl World {
     fn check_pickup_logic(&mut self) {
         for (coord, robots) in &self.pick_up_check {
-            let gold_bars = self.grid.get_mut_cell(*coord).unwrap().get_gold_amount();
+            let cell = self.grid.get_mut_cell(*coord).unwrap();
+            let gold_bars = cell.get_gold_amount();
+            let robots_required = cell.get_robots_required(self.pickup_config.robots_required);
             match gold_bars {
                 Some(n) => {
-                    if robots.len() < 2 {
-                        continue;
-                    } else {
-                        let mut reds: Vec<char> = Vec::new();
-                        let mut blues: Vec<char> = Vec::new();
-                        for (id, team) in robots {
-                            match *team {
-                                Team::Red => reds.push(*id),
-                                Team::Blue => blues.push(*id)
-                            }
-                        }
-                        let (red_is_able_to_pick, blue_is_able_to_pick) = Self::teams_that_picks(reds.len(), blues.len(), n);
-                        if red_is_able_to_pick {
-                            let picked = self.red_team.pickup_gold(reds[0], reds[1]);
-                            if picked {
-                                self.grid.get_mut_cell(*coord).unwrap().remove_gold();
-                                // println!("{}{} and {} has {} picked up a {}", "|".red(), reds[0].to_string().red().bold(), reds[1].to_string().red().bold(), "SUCCESSFULLY".green().bold(), "GOLD BAR".yellow().bold())
-                            }
-                        }
-                        if blue_is_able_to_pick {
-                            let picked = self.blue_team.pickup_gold(blues[0], blues[1]);
-                            if picked {
-                                self.grid.get_mut_cell(*coord).unwrap().remove_gold();
-                                // println!("{}{} and {} has {} picked up a {}", "|".blue(), blues[0].to_string().blue().bold(), blues[1].to_string().blue().bold(), "SUCCESSFULLY".green().bold(), "GOLD BAR".yellow().bold())
-                            }
+                    let mut reds: Vec<char> = Vec::new();
+                    let mut blues: Vec<char> = Vec::new();
+                    for (id, team) in robots {
+                        match *team {
+                            Team::Red => reds.push(*id),
+                            Team::Blue => blues.push(*id)
                         }
                     }
+                    reds.sort();
+                    blues.sort();
+                    let (red_is_able_to_pick, blue_is_able_to_pick) = Self::teams_that_picks(reds.len(), blues.len(), robots_required, n, &self.pickup_config);
+                    Self::pick_up(&mut self.red_team, &mut self.grid, *coord, &reds, robots_required, red_is_able_to_pick, self.pickup_config.group_size);
+                    Self::pick_up(&mut self.blue_team, &mut self.grid, *coord, &blues, robots_required, blue_is_able_to_pick, self.pickup_config.group_size);
                 },
+                // Robots keep the PickupNoGold outcome
                 None => continue
             }
         }
     }
 
-    fn is_invalid_pickup(red_robots: usize, blue_robots: usize, golds: u8) -> bool {
-        (red_robots > 2 && blue_robots > 2) ||
-            (red_robots < 2 && blue_robots < 2) ||
-            (red_robots == 2 && blue_robots == 2 && golds < 2)
+    // The first `robots_required` robots lift a bar when the team picks, every robot hears how its pickup went
+    fn pick_up(team: &mut RobotManager, grid: &mut Grid, coord: Coord, ids: &[char], robots_required: usize, picks: bool, group_size: GroupSize) {
+        if ids.is_empty() {
+            return;
+        }
+        if !Self::is_able_to_pick(ids.len(), robots_required, group_size) {
+            team.report_outcome(ids, ActionOutcome::PickupGroupMismatch);
+        } else if !picks {
+            team.report_outcome(ids, ActionOutcome::PickupContention);
+        } else if team.is_any_carrying(&ids[..robots_required]) {
+            team.report_outcome(ids, ActionOutcome::PickupAlreadyCarrying);
+        } else {
+            let value = grid.get_mut_cell(coord).unwrap().remove_gold().unwrap();
+            team.pickup_gold(&ids[..robots_required], value);
+            team.report_outcome(&ids[..robots_required], ActionOutcome::PickedUp(value));
+            team.report_outcome(&ids[robots_required..], ActionOutcome::PickupGroupMismatch);
+        }
     }
 
-    fn teams_that_picks(red_robots: usize, blue_robots: usize, golds: u8) -> (bool, bool) {
-        if Self::is_invalid_pickup(red_robots, blue_robots, golds) {
-            (false, false)
-        } else {
-            (red_robots == 2, blue_robots == 2)
+    fn is_able_to_pick(robots: usize, robots_required: usize, group_size: GroupSize) -> bool {
+        match group_size {
+            GroupSize::Exact => robots == robots_required,
+            GroupSize::AtLeast => robots >= robots_required,
         }
     }
+
+    fn teams_that_picks(red_robots: usize, blue_robots: usize, robots_required: usize, golds: u8, pickup_config: &PickupConfig) -> (bool, bool) {
+        let red_is_able_to_pick = Self::is_able_to_pick(red_robots, robots_required, pickup_config.group_size);
+        let blue_is_able_to_pick = Self::is_able_to_pick(blue_robots, robots_required, pickup_config.group_size);
+        if !(red_is_able_to_pick && blue_is_able_to_pick) || golds >= 2 {
+            return (red_is_able_to_pick, blue_is_able_to_pick);
+        }
+        match pickup_config.contention_rule {
+            ContentionRule::NeitherPicks => (false, false),
+            ContentionRule::LargerGroup => (red_robots > blue_robots, blue_robots > red_robots),
+            ContentionRule::Random => {
+                let red_wins = rand::random_bool(0.5);
+                (red_wins, !red_wins)
+            },
+        }
+    }
+
+    // Carriers whose whole group is still carrying, one entry per group
+    fn carrying_groups(carriers: Option<Vec<&mut Robot>>) -> Vec<Vec<&mut Robot>> {
+        let mut carriers: HashMap<char, &mut Robot> = match carriers {
+            Some(carriers) => carriers.into_iter().map(|carrier| (carrier.get_id(), carrier)).collect(),
+            None => return Vec::new(),
+        };
+        let mut ids: Vec<char> = carriers.keys().copied().collect();
+        ids.sort();
+        let mut groups = Vec::new();
+        for id in ids {
+            let Some(carrier) = carriers.get(&id) else {
+                continue;
+            };
+            let mut group_ids = carrier.get_carrying_group();
+            group_ids.push(id);
+            if group_ids.iter().all(|group_id| carriers.contains_key(group_id)) {
+                groups.push(group_ids.iter().map(|group_id| carriers.remove(group_id).unwrap()).collect());
+            }
+        }
+        groups
+    }
 }
 
 // Fumble logic
 impl World {
     fn check_fumble(&mut self) {
         let add_gold_coords = self.get_gold_coords();
-        for gold_coord in add_gold_coords {
-            self.grid.get_mut_cell(gold_coord).unwrap().add_gold();
+        for (gold_coord, value) in add_gold_coords {
+            self.grid.get_mut_cell(gold_coord).unwrap().add_gold(value);
         }
     }
 
-    fn get_gold_coords(&mut self) -> Vec<Coord> {
+    fn get_gold_coords(&mut self) -> Vec<(Coord, u8)> {
+        let deposit_boxes: Vec<Coord> = self.red_deposit_boxes.iter().chain(self.blue_deposit_boxes.iter()).copied().collect();
         let red_carriers = self.red_team.get_carrying_robot();
+        let mut add_gold_coords: Vec<(Coord, u8)> = Vec::new();
+        let (red_drops, red_put_downs, red_fumbles) = Self::get_drop_coords(red_carriers, &deposit_boxes, &mut add_gold_coords);
+        self.red_team.record_drops(red_drops, red_put_downs, red_fumbles);
         let blue_carriers = self.blue_team.get_carrying_robot();
-        let mut add_gold_coords: Vec<Coord> = Vec::new();
-        Self::get_drop_coords(red_carriers, &mut add_gold_coords);
-        Self::get_drop_coords(blue_carriers, &mut add_gold_coords);
+        let (blue_drops, blue_put_downs, blue_fumbles) = Self::get_drop_coords(blue_carriers, &deposit_boxes, &mut add_gold_coords);
+        self.blue_team.record_drops(blue_drops, blue_put_downs, blue_fumbles);
         add_gold_coords
     }
 
-    fn get_drop_coords(carriers: Option<Vec<&mut Robot>>, add_gold_coords: &mut Vec<Coord>) {
-        match carriers {
-            Some(carriers) => {
-                let mut robot_pos: HashMap<char, &mut Robot> = HashMap::new();
-                for carrier in carriers {
-                    let partner_id = carrier.get_pair_id().unwrap();
-                    let partner_coord = robot_pos.remove(&partner_id);
-                    match partner_coord {
-                        Some(pair_robot) => {
-                            let carrier_latest_action = carrier.get_latest_action();
-                            let pair_latest_action = pair_robot.get_latest_action();
-                            let drop = (carrier_latest_action != pair_latest_action) |
-                                       (carrier_latest_action == Action::PickUp && carrier.was_carrying()) |
-                                       (pair_latest_action == Action::PickUp && pair_robot.was_carrying());
-                            if drop {
-                                add_gold_coords.push(carrier.drop_gold());
-                                pair_robot.drop_gold();
-                            }
-                        },
-                        None => {
-                            robot_pos.insert(carrier.get_id(), carrier);
-                        }
-                    }
+    // A group puts its bar down when every member drops together, or when one of them is too low on energy to carry it
+    // home while they still move in step, except on a deposit box where bars can only be delivered.
+    // It fumbles the bar when its members act differently, one of them crashed, ran flat or was tackled, or one picks up again.
+    // Returns the number of voluntary drops, low battery put-downs and fumbles
+    fn get_drop_coords(carriers: Option<Vec<&mut Robot>>, deposit_boxes: &[Coord], add_gold_coords: &mut Vec<(Coord, u8)>) -> (u32, u32, u32) {
+        let (mut drops, mut put_downs, mut fumbles) = (0, 0, 0);
+        for group in Self::carrying_groups(carriers) {
+            let first_action = group[0].get_latest_action();
+            let intact = group.iter().all(|carrier| !carrier.is_crashed() && !carrier.is_tackled());
+            let all_drop = group.iter().all(|carrier| carrier.get_latest_action() == Action::Drop);
+            let low_battery = group.iter().all(|carrier| carrier.get_latest_action() == first_action && !carrier.is_flat())
+                && group.iter().any(|carrier| carrier.is_too_low_to_carry());
+            let put_down = intact && (all_drop || low_battery);
+            if put_down && !deposit_boxes.contains(&group[0].get_coord()) {
+                let value = group[0].get_carried_value();
+                let mut drop_coord = None;
+                for carrier in group {
+                    drop_coord = Some(carrier.put_down_gold(!all_drop));
                 }
-            },
-            None => ()
+                add_gold_coords.push((drop_coord.unwrap(), value));
+                if all_drop {
+                    drops += 1;
+                } else {
+                    put_downs += 1;
+                }
+                continue;
+            }
+            let drop = group.iter().any(|carrier| {
+                carrier.is_crashed() |
+                    carrier.is_flat() |
+                    carrier.is_tackled() |
+                    (carrier.get_latest_action() != first_action) |
+                    (carrier.get_latest_action() == Action::PickUp && carrier.was_carrying())
+            });
+            if drop {
+                let value = group[0].get_carried_value();
+                let mut drop_coord = None;
+                for carrier in group {
+                    drop_coord = Some(carrier.drop_gold());
+                }
+                add_gold_coords.push((drop_coord.unwrap(), value));
+                fumbles += 1;
+            }
         }
+        (drops, put_downs, fumbles)
     }
 
 }
@@ -350,62 +736,56 @@ impl World {
         let blue_carriers = self.blue_team.get_carrying_robot();
         println!("red_carriers: {:?}", red_carriers);
         println!("blue_carriers: {:?}", blue_carriers);
-        match red_carriers {
-            Some(carriers) => {
-                let mut robot_pos: HashMap<char, &mut Robot> = HashMap::new();
-                for carrier in carriers {
-                    let partner_id = carrier.get_pair_id().unwrap();
-                    let partner_coord = robot_pos.remove(&partner_id);
-                    match partner_coord {
-                        Some(pair_robot) => {
-                            if pair_robot.get_coord() == carrier.get_coord() && carrier.get_coord() == self.red_deposit_box {
-                                carrier.score_gold();
-                                pair_robot.score_gold();
-                                self.red_score += 1;
-                                carrier.scored();
-                                pair_robot.scored();
-                                // println!("{}{}: {}", "|".red(), "RED".red().bold(), self.red_score.to_string().red());
-                                // println!("{}{}: {}", "|".blue(), "BLU".blue().bold(), self.blue_score.to_string().blue());
-                                self.grid.get_mut_cell(self.red_deposit_box).unwrap().increment_score();
-                            }
-                        },
-                        None => {
-                            robot_pos.insert(carrier.get_id(), carrier);
-                        }
-                    }
+        let red_delivered = Self::deliver_groups(red_carriers, &self.red_deposit_boxes, &self.deposit_rules, &mut self.grid, self.turn);
+        for (deposit_box, value, with_byzantine) in red_delivered {
+            self.red_score += value as u32;
+            self.red_team.record_score(value as u32, with_byzantine);
+            self.grid.get_mut_cell(deposit_box).unwrap().increment_score(value);
+        }
+        let blue_delivered = Self::deliver_groups(blue_carriers, &self.blue_deposit_boxes, &self.deposit_rules, &mut self.grid, self.turn);
+        for (deposit_box, value, with_byzantine) in blue_delivered {
+            self.blue_score += value as u32;
+            self.blue_team.record_score(value as u32, with_byzantine);
+            self.grid.get_mut_cell(deposit_box).unwrap().increment_score(value);
+        }
+    }
+
+    // Groups score once every member stands on the same deposit box while it is open and has room left,
+    // returns the box and value of each delivered bar and whether a Byzantine robot carried it
+    fn deliver_groups(carriers: Option<Vec<&mut Robot>>, deposit_boxes: &[Coord], deposit_rules: &HashMap<Coord, BoxRules>, grid: &mut Grid, turn: u32) -> Vec<(Coord, u8, bool)> {
+        let mut delivered = Vec::new();
+        for group in Self::carrying_groups(carriers) {
+            let deposit_box = group[0].get_coord();
+            if !deposit_boxes.contains(&deposit_box) || !group.iter().all(|carrier| carrier.get_coord() == deposit_box) {
+                continue;
+            }
+            let value = group[0].get_carried_value();
+            let rules = deposit_rules[&deposit_box];
+            if !rules.is_open(turn) {
+                println!("Deposit box at {:?} is closed, {:?} wait", deposit_box, group.iter().map(|carrier| carrier.get_id()).collect::<Vec<char>>());
+                for carrier in group {
+                    carrier.report_outcome(ActionOutcome::DeliveryRefused);
                 }
-            },
-            None => ()
-        }
-        match blue_carriers {
-            Some(carriers) => {
-                let mut robot_pos: HashMap<char, &mut Robot> = HashMap::new();
-                for carrier in carriers {
-                    let partner_id = carrier.get_pair_id().unwrap();
-                    let partner_coord = robot_pos.remove(&partner_id);
-                    match partner_coord {
-                        Some(pair_robot) => {
-                            if pair_robot.get_coord() == carrier.get_coord() && carrier.get_coord() == self.blue_deposit_box {
-                                carrier.score_gold();
-                                pair_robot.score_gold();
-                                self.blue_score += 1;
-                                carrier.scored();
-                                pair_robot.scored();
-                                // println!("{}{}: {}", "|".red(), "RED".red().bold(), self.red_score.to_string().red());
-                                // println!("{}{}: {}", "|".blue(), "BLU".blue().bold(), self.blue_score.to_string().blue());
-                                self.grid.get_mut_cell(self.blue_deposit_box).unwrap().increment_score();
-                            }
-                        },
-                        None => {
-                            robot_pos.insert(carrier.get_id(), carrier);
-                        }
-                    }
+                continue;
+            }
+            let deposited = grid.get_cell(deposit_box).unwrap().get_deposited().unwrap_or(0);
+            let already_delivered: u32 = delivered.iter().filter(|(coord, _, _)| *coord == deposit_box).map(|(_, value, _)| *value as u32).sum();
+            if !rules.has_room(deposited + already_delivered, value) {
+                println!("Deposit box at {:?} is full, {:?} turned away", deposit_box, group.iter().map(|carrier| carrier.get_id()).collect::<Vec<char>>());
+                for carrier in group {
+                    carrier.report_outcome(ActionOutcome::DeliveryRefused);
                 }
-            },
-            None => ()
+                continue;
+            }
+            delivered.push((deposit_box, value, group.iter().any(|carrier| carrier.is_adversarial())));
+            for carrier in group {
+                carrier.score_gold();
+                carrier.scored();
+            }
         }
+        delivered
     }
-    
+
     pub fn increment_score(&mut self, team: Team) {
         match team {
             Team::Blue => self.blue_score += 1,
@@ -424,6 +804,34 @@ impl World {
         println!("Pickup check: {:?}", self.pick_up_check);
     }
 
+    pub fn print_metrics(&self) {
+        self.blue_team.print_metrics();
+        self.red_team.print_metrics();
+    }
+
+    pub fn print_safety_report(&self) {
+        self.blue_team.print_safety_report();
+        self.red_team.print_safety_report();
+    }
+
+    pub fn print_partition_report(&self) {
+        self.blue_team.print_partition_report();
+        self.red_team.print_partition_report();
+    }
+
+    pub fn print_knowledge_coverage(&mut self) {
+        let cells = self.width * self.height;
+        for (team, robot_manager) in [(Team::Blue, &mut self.blue_team), (Team::Red, &mut self.red_team)] {
+            let mut robots = robot_manager.get_robots();
+            robots.sort_by_key(|robot| robot.get_id());
+            for robot in robots {
+                let (self_observed, known) = robot.get_knowledge_coverage();
+                let (sync_sent, sync_merged) = robot.get_kb_sync_counts();
+                println!("{}Robot {} knows {}/{} cells ({} observed itself) - sent {} sync messages and merged {} observations", team.style("|".to_string()), team.style(robot.get_id().to_string()).bold(), known, cells, self_observed, sync_sent, sync_merged);
+            }
+        }
+    }
+
     pub fn print_robots(&mut self) {
         for blue_robot in &self.blue_team.get_robots() {
             println!("{}{:?}", "|".blue(), blue_robot);
@@ -433,3 +841,163 @@ impl World {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::pickup::RobotsRequired;
+    use crate::robot::Direction;
+
+    // Bars on (0, 0) and a blue team of n robots standing on them, their box is next door
+    fn cell_with_robots(bars: &[u8], n_robots: u8) -> (Grid, RobotManager) {
+        let mut gold = Cell::new((0, 0), 0.0, 1, RobotsRequired::Fixed(2));
+        for bar in bars {
+            gold.add_gold(*bar);
+        }
+        let mut grid = Grid::new(vec![vec![gold, Cell::new((1, 0), 0.0, 1, RobotsRequired::Fixed(2))]], 2, 1);
+        let deposit_box = Coord::new(1, 0);
+        let deposit_rules = HashMap::from([(deposit_box, BoxRules { capacity: None, opening_hours: None })]);
+        let (robots, message_board) = World::spawn_robots(1, 1, &mut grid, n_robots, Team::Blue, &[deposit_box], &deposit_rules);
+        (grid, RobotManager::new(Team::Blue, robots, message_board))
+    }
+
+    // The robots try to pick up, then the world settles it as it does at the end of a turn
+    fn pick_up(team: &mut RobotManager, grid: &mut Grid, ids: &[char], robots_required: usize, group_size: GroupSize) {
+        for id in ids {
+            team.get_robot_by_id(*id).unwrap().take_action(&Action::PickUp, grid);
+        }
+        World::pick_up(team, grid, Coord::new(0, 0), ids, robots_required, true, group_size);
+    }
+
+    fn outcome(team: &mut RobotManager, id: char) -> Option<ActionOutcome> {
+        team.get_robot_by_id(id).unwrap().get_latest_outcome()
+    }
+
+    #[test]
+    fn three_robots_lift_a_bar_that_needs_three() {
+        let (mut grid, mut team) = cell_with_robots(&[2], 3);
+        pick_up(&mut team, &mut grid, &['a', 'b', 'c'], 3, GroupSize::Exact);
+        for id in ['a', 'b', 'c'] {
+            assert_eq!(outcome(&mut team, id), Some(ActionOutcome::PickedUp(2)));
+            let mut group = team.get_robot_by_id(id).unwrap().get_carrying_group();
+            group.push(id);
+            group.sort();
+            assert_eq!(group, vec!['a', 'b', 'c']);
+        }
+        assert_eq!(grid.get_cell(Coord::new(0, 0)).unwrap().get_gold_amount(), None);
+    }
+
+    #[test]
+    fn too_few_robots_leave_the_bar() {
+        let (mut grid, mut team) = cell_with_robots(&[2], 2);
+        pick_up(&mut team, &mut grid, &['a', 'b'], 3, GroupSize::Exact);
+        for id in ['a', 'b'] {
+            assert_eq!(outcome(&mut team, id), Some(ActionOutcome::PickupGroupMismatch));
+            assert!(!team.get_robot_by_id(id).unwrap().is_carrying());
+        }
+        assert_eq!(grid.get_cell(Coord::new(0, 0)).unwrap().get_gold_amount(), Some(1));
+    }
+
+    #[test]
+    fn an_extra_robot_spoils_an_exact_pickup() {
+        let (mut grid, mut team) = cell_with_robots(&[1], 4);
+        pick_up(&mut team, &mut grid, &['a', 'b', 'c', 'd'], 3, GroupSize::Exact);
+        for id in ['a', 'b', 'c', 'd'] {
+            assert_eq!(outcome(&mut team, id), Some(ActionOutcome::PickupGroupMismatch));
+        }
+        assert_eq!(grid.get_cell(Coord::new(0, 0)).unwrap().get_gold_amount(), Some(1));
+    }
+
+    #[test]
+    fn the_lowest_ids_lift_when_at_least_enough_are_there() {
+        let (mut grid, mut team) = cell_with_robots(&[1], 4);
+        pick_up(&mut team, &mut grid, &['a', 'b', 'c', 'd'], 3, GroupSize::AtLeast);
+        for id in ['a', 'b', 'c'] {
+            assert_eq!(outcome(&mut team, id), Some(ActionOutcome::PickedUp(1)));
+        }
+        assert_eq!(outcome(&mut team, 'd'), Some(ActionOutcome::PickupGroupMismatch));
+        assert!(!team.get_robot_by_id('d').unwrap().is_carrying());
+    }
+
+    #[test]
+    fn a_group_with_a_carrier_does_not_lift_another_bar() {
+        let (mut grid, mut team) = cell_with_robots(&[1, 1], 3);
+        pick_up(&mut team, &mut grid, &['a', 'b'], 2, GroupSize::Exact);
+        pick_up(&mut team, &mut grid, &['b', 'c'], 2, GroupSize::Exact);
+        assert_eq!(outcome(&mut team, 'c'), Some(ActionOutcome::PickupAlreadyCarrying));
+        assert_eq!(grid.get_cell(Coord::new(0, 0)).unwrap().get_gold_amount(), Some(1));
+    }
+
+    #[test]
+    fn estimated_group_sizes_follow_the_rule() {
+        let reported = Cell::from_observation(Coord::new(0, 0), Some((2, 5)), 0, 0);
+        assert_eq!(reported.get_robots_required(RobotsRequired::Fixed(3)), 3);
+        assert_eq!(reported.get_robots_required(RobotsRequired::PerCell(2, 4)), 2);
+        assert_eq!(reported.get_robots_required(RobotsRequired::ByValue), 4);
+        let mut gold = Cell::new((0, 0), 0.0, 1, RobotsRequired::Fixed(2));
+        gold.add_gold(1);
+        gold.add_gold(2);
+        assert_eq!(gold.get_robots_required(RobotsRequired::ByValue), 3);
+    }
+
+    // A 3x3 world holding only the given blue robots, moves limited by the given collision config
+    fn world_with(robots: &[(char, (usize, usize), Direction)], collision_config: CollisionConfig) -> World {
+        let mut world = World::new(3, 3, 0.0, 1, 0, false);
+        let rows = (0..3).rev().map(|y| (0..3).map(|x| Cell::new((x, y), 0.0, 1, RobotsRequired::Fixed(2))).collect()).collect();
+        let mut grid = Grid::new(rows, 3, 3);
+        let (_, message_board) = World::spawn_robots(3, 3, &mut grid, 0, Team::Blue, &[], &HashMap::new());
+        let mut team = HashMap::new();
+        for (id, (x, y), facing) in robots {
+            let coord = Coord::new(*x, *y);
+            message_board.lock().unwrap().insert(*id, MessageBox::new());
+            message_board.lock().unwrap().set_position(*id, coord);
+            let robot = Robot::new(*id, Team::Blue, coord, *facing, Arc::clone(&message_board), Vec::new(), HashMap::new());
+            grid.get_mut_cell(coord).unwrap().add_bot(&robot);
+            team.insert(*id, robot);
+        }
+        world.grid = grid;
+        world.blue_team = RobotManager::new(Team::Blue, team, message_board);
+        world.collision_config = collision_config;
+        world
+    }
+
+    fn one_per_cell(rule: CollisionRule) -> CollisionConfig {
+        CollisionConfig { cell_capacity: Some(1), team_capacity: None, rule }
+    }
+
+    fn all_move(ids: &[char]) -> Vec<(Team, char, Action)> {
+        ids.iter().map(|id| (Team::Blue, *id, Action::Move)).collect()
+    }
+
+    #[test]
+    fn robots_swapping_cells_pass_each_other() {
+        let mut world = world_with(&[('a', (0, 1), Right), ('b', (1, 1), Left)], one_per_cell(CollisionRule::BothBounce));
+        assert!(world.resolve_collisions(&all_move(&['a', 'b'])).is_empty());
+    }
+
+    #[test]
+    fn lowest_id_gets_the_crowded_cell() {
+        let mut world = world_with(&[('c', (0, 1), Right), ('a', (2, 1), Left), ('b', (1, 0), Up)], one_per_cell(CollisionRule::LowerIdFirst));
+        assert_eq!(world.resolve_collisions(&all_move(&['a', 'b', 'c'])), HashSet::from(['b', 'c']));
+    }
+
+    #[test]
+    fn everyone_bounces_off_a_crowded_cell() {
+        let mut world = world_with(&[('a', (0, 1), Right), ('b', (2, 1), Left), ('c', (1, 0), Up)], one_per_cell(CollisionRule::BothBounce));
+        assert_eq!(world.resolve_collisions(&all_move(&['a', 'b', 'c'])), HashSet::from(['a', 'b', 'c']));
+    }
+
+    #[test]
+    fn a_robot_staying_put_fills_its_cell() {
+        // b stays on (1, 1), so a is turned back and c, following a, is turned back too
+        let mut world = world_with(&[('a', (0, 1), Right), ('b', (1, 1), Up), ('c', (0, 0), Up)], one_per_cell(CollisionRule::LowerIdFirst));
+        assert_eq!(world.resolve_collisions(&all_move(&['a', 'c'])), HashSet::from(['a', 'c']));
+    }
+
+    #[test]
+    fn robots_move_freely_without_a_capacity() {
+        let config = CollisionConfig { cell_capacity: None, team_capacity: None, rule: CollisionRule::BothBounce };
+        let mut world = world_with(&[('a', (0, 1), Right), ('b', (2, 1), Left)], config);
+        assert!(world.resolve_collisions(&all_move(&['a', 'b'])).is_empty());
+    }
+}
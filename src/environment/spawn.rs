@@ -0,0 +1,81 @@
+use colored::Colorize;
+use crate::config::spawn::{SpawnConfig, SpawnProcess};
+use crate::environment::cell::Cell;
+use crate::environment::grid::Grid;
+use crate::util::Coord;
+
+// Adds gold to the grid over time
+pub struct GoldSpawner {
+    process: SpawnProcess,
+    max_gold: u8,
+    // Announced waves: the turn they land on and where
+    waves: Vec<(u32, Vec<Coord>)>,
+}
+
+// Constructor
+impl GoldSpawner {
+    pub fn new(width: usize, height: usize) -> GoldSpawner {
+        let SpawnConfig { process, max_gold } = SpawnConfig::new();
+        // A centre off the grid leaves an empty range to draw from
+        if let SpawnProcess::Hotspots(hotspots) = process {
+            for hotspot in hotspots {
+                assert!(hotspot.x < width && hotspot.y < height, "Gold hotspot at ({}, {}) lies outside the {}x{} grid", hotspot.x, hotspot.y, width, height);
+            }
+        }
+        Self {
+            process,
+            max_gold,
+            waves: Vec::new(),
+        }
+    }
+}
+
+// Spawn logic
+impl GoldSpawner {
+    // Returns the number of bars added this turn
+    pub fn spawn(&mut self, turn: u32, grid: &mut Grid) -> u32 {
+        let (width, height) = (grid.get_width(), grid.get_height());
+        let coords: Vec<Coord> = match self.process {
+            SpawnProcess::Disabled => Vec::new(),
+            SpawnProcess::Uniform(probability) => {
+                (0..width).flat_map(|x| (0..height).map(move |y| Coord::new(x, y)))
+                    .filter(|_| rand::random_bool(probability))
+                    .collect()
+            },
+            SpawnProcess::Hotspots(hotspots) => {
+                hotspots.iter()
+                    .filter(|hotspot| rand::random_bool(hotspot.probability))
+                    .map(|hotspot| Coord::random(
+                        hotspot.x.saturating_sub(hotspot.radius)..(hotspot.x + hotspot.radius + 1).min(width),
+                        hotspot.y.saturating_sub(hotspot.radius)..(hotspot.y + hotspot.radius + 1).min(height),
+                    ))
+                    .collect()
+            },
+            SpawnProcess::Waves { period, bars, warning } => {
+                if period > 0 && (turn + warning) % period == 0 {
+                    let wave: Vec<Coord> = (0..bars).map(|_| Coord::random(0..width, 0..height)).collect();
+                    println!("{} {} bars will land at turn {}: {:?}", "GOLD WAVE".yellow().bold(), bars, turn + warning, wave);
+                    self.waves.push((turn + warning, wave));
+                }
+                let (landing, waiting): (Vec<(u32, Vec<Coord>)>, Vec<(u32, Vec<Coord>)>) = self.waves.drain(..).partition(|(landing_turn, _)| *landing_turn <= turn);
+                self.waves = waiting;
+                landing.into_iter().flat_map(|(_, wave)| wave).collect()
+            },
+        };
+        let mut spawned = 0;
+        for coord in coords {
+            let Some(cell) = grid.get_mut_cell(coord) else {
+                continue;
+            };
+            if cell.is_deposit_box().is_some() || cell.get_gold_amount().unwrap_or(0) >= self.max_gold {
+                continue;
+            }
+            cell.add_gold(Cell::random_bar_value());
+            spawned += 1;
+        }
+        if spawned > 0 {
+            println!("{} {} bars", "GOLD SPAWNED".yellow().bold(), spawned);
+        }
+        spawned
+    }
+}
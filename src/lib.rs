@@ -3,4 +3,5 @@ pub mod environment;
 pub mod robot;
 pub mod util;
 pub mod config;
-pub mod communication;
\ No newline at end of file
+pub mod communication;
+pub mod metrics;
\ No newline at end of file
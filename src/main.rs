@@ -16,11 +16,16 @@ fn main() {
         n_robots,
         manual,
         turns,
+        ..
     } = Config::new();
     let mut world = World::new(width, height, p_gold, max_gold, n_robots, manual);
     let LoggerConfig {
         current_grid,
         robot_status,
+        knowledge_coverage,
+        metrics,
+        safety_report,
+        partition_report,
         ..
     } = LoggerConfig::new();
     println!("{}", "Initial Grid".bold());
@@ -42,4 +47,21 @@ fn main() {
     }
     println!("{}", "Final Grid".bold());
     world.print_grid();
+    println!("Total gold amount (initial and spawned): {}", world.total_gold_amount);
+    if knowledge_coverage {
+        println!("\n{}", "Knowledge Coverage".bold());
+        world.print_knowledge_coverage();
+    }
+    if metrics {
+        println!("\n{}", "Metrics".bold());
+        world.print_metrics();
+    }
+    if safety_report {
+        println!("\n{}", "Safety Report".bold());
+        world.print_safety_report();
+    }
+    if partition_report {
+        println!("\n{}", "Partition Report".bold());
+        world.print_partition_report();
+    }
 }
\ No newline at end of file
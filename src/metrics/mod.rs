@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::communication::message::MessageType;
+use crate::robot::RobotState;
+
+#[derive(Default)]
+pub struct TeamMetrics {
+    // Communication cost
+    pub messages_sent: u32,
+    pub bytes_sent: u32,
+    pub messages_by_type: HashMap<MessageType, u32>,
+    pub queued_sends: u32,
+    pub queue_overflows: u32,
+    pub dropped_sends: u32,
+    pub lost_in_transit: u32,
+    pub partitioned: u32,
+    pub jammed: u32,
+    pub overheard: u32,
+    pub messages_delivered: u32,
+    pub total_latency: u32,
+    pub max_latency: u32,
+    pub stale_discarded: u32,
+    pub expired_discarded: u32,
+    pub rejected_unauthenticated: u32,
+
+    // Liveness
+    pub timeouts: HashMap<RobotState, u32>,
+    pub crashes: u32,
+    pub restarts: u32,
+
+    // Robustness
+    pub byzantine_robots: u32,
+    pub adversarial_messages: u32,
+    pub refused_pickups: u32,
+    pub byzantine_decisions: u32,
+    pub byzantine_decision_turns: u32,
+    pub fake_report_decisions: u32,
+    pub honest_score: u32,
+    pub byzantine_score: u32,
+
+    // Movement
+    pub bounced_moves: u32,
+    pub fumbles: u32,
+    pub voluntary_drops: u32,
+
+    // Tackling
+    pub tackles: u32,
+    pub successful_tackles: u32,
+
+    // Energy
+    pub ran_flat: u32,
+    pub recharge_trips: u32,
+    pub low_battery_put_downs: u32,
+    pub unpowered_sends: u32,
+
+    // Consensus
+    pub decisions: u32,
+    pub total_decision_turns: u32,
+}
+
+impl TeamMetrics {
+    pub fn new() -> TeamMetrics {
+        Self::default()
+    }
+
+    pub fn record_send(&mut self, msg_type: MessageType, bytes: u32) {
+        self.messages_sent += 1;
+        self.bytes_sent += bytes;
+        *self.messages_by_type.entry(msg_type).or_insert(0) += 1;
+    }
+
+    pub fn record_delivery(&mut self, latency: u32) {
+        self.messages_delivered += 1;
+        self.total_latency += latency;
+        self.max_latency = self.max_latency.max(latency);
+    }
+
+    pub fn record_timeout(&mut self, state: RobotState) {
+        *self.timeouts.entry(state).or_insert(0) += 1;
+    }
+
+    // Decisions a Byzantine robot took part in are also counted apart, to compare against the honest ones
+    pub fn record_decision(&mut self, turns: u32, with_byzantine: bool, fake_report: bool) {
+        self.decisions += 1;
+        self.total_decision_turns += turns;
+        if with_byzantine {
+            self.byzantine_decisions += 1;
+            self.byzantine_decision_turns += turns;
+        }
+        if fake_report {
+            self.fake_report_decisions += 1;
+        }
+    }
+
+    pub fn record_score(&mut self, value: u32, with_byzantine: bool) {
+        if with_byzantine {
+            self.byzantine_score += value;
+        } else {
+            self.honest_score += value;
+        }
+    }
+
+    pub fn average_latency(&self) -> f64 {
+        if self.messages_delivered == 0 {
+            0.0
+        } else {
+            self.total_latency as f64 / self.messages_delivered as f64
+        }
+    }
+
+    pub fn average_decision_turns(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.total_decision_turns as f64 / self.decisions as f64
+        }
+    }
+
+    pub fn honest_decisions(&self) -> u32 {
+        self.decisions - self.byzantine_decisions
+    }
+
+    pub fn average_honest_decision_turns(&self) -> f64 {
+        if self.honest_decisions() == 0 {
+            0.0
+        } else {
+            (self.total_decision_turns - self.byzantine_decision_turns) as f64 / self.honest_decisions() as f64
+        }
+    }
+
+    pub fn average_byzantine_decision_turns(&self) -> f64 {
+        if self.byzantine_decisions == 0 {
+            0.0
+        } else {
+            self.byzantine_decision_turns as f64 / self.byzantine_decisions as f64
+        }
+    }
+}
+
+// Print functions
+impl Display for TeamMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "  Messages sent: {} ({} bytes)", self.messages_sent, self.bytes_sent)?;
+        writeln!(f, "  Queued sends: {} - Queue overflows: {} - Dropped sends: {} - Lost in transit: {} - Cut by partitions: {} - Jammed: {}", self.queued_sends, self.queue_overflows, self.dropped_sends, self.lost_in_transit, self.partitioned, self.jammed)?;
+        writeln!(f, "  Enemy messages overheard: {}", self.overheard)?;
+        writeln!(f, "  Messages delivered: {} - Average latency: {:.2} turns - Max latency: {} turns", self.messages_delivered, self.average_latency(), self.max_latency)?;
+        writeln!(f, "  Stale discarded: {} - Expired discarded: {} - Rejected unauthenticated: {}", self.stale_discarded, self.expired_discarded, self.rejected_unauthenticated)?;
+        let mut messages_by_type: Vec<(&MessageType, &u32)> = self.messages_by_type.iter().collect();
+        messages_by_type.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        writeln!(f, "  By type: {:?}", messages_by_type)?;
+        writeln!(f, "  Timeouts: {:?}", self.timeouts)?;
+        writeln!(f, "  Crashes: {} - Restarts: {}", self.crashes, self.restarts)?;
+        writeln!(f, "  Byzantine robots: {} - Adversarial messages: {} - Refused pickups: {}", self.byzantine_robots, self.adversarial_messages, self.refused_pickups)?;
+        writeln!(f, "  Honest decisions: {} ({:.2} turns) - With Byzantine robots: {} ({:.2} turns) - On fake reports: {}", self.honest_decisions(), self.average_honest_decision_turns(), self.byzantine_decisions, self.average_byzantine_decision_turns(), self.fake_report_decisions)?;
+        writeln!(f, "  Scored by honest groups: {} - By groups with Byzantine robots: {}", self.honest_score, self.byzantine_score)?;
+        writeln!(f, "  Bounced moves: {} - Fumbles: {} - Voluntary drops: {}", self.bounced_moves, self.fumbles, self.voluntary_drops)?;
+        writeln!(f, "  Tackles: {} - Successful tackles: {}", self.tackles, self.successful_tackles)?;
+        writeln!(f, "  Ran flat: {} - Recharge trips: {} - Low battery put-downs: {} - Unpowered sends: {}", self.ran_flat, self.recharge_trips, self.low_battery_put_downs, self.unpowered_sends)?;
+        write!(f, "  Decisions: {} - Average turns to decide: {:.2}", self.decisions, self.average_decision_turns())
+    }
+}
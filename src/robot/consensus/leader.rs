@@ -0,0 +1,70 @@
+use crate::communication::message::{Message, MessageContent, MessageType};
+use crate::robot::consensus::{Consensus, ConsensusContext, Outgoing};
+
+// The robot with the lowest id in the cluster decides its own proposal and tells the others
+pub struct LeaderConsensus {
+    confirmed: Option<Message>,
+    decision: Option<Message>,
+}
+
+// Constructor
+impl LeaderConsensus {
+    pub fn new() -> LeaderConsensus {
+        Self {
+            confirmed: None,
+            decision: None,
+        }
+    }
+
+    fn leader(context: &ConsensusContext) -> char {
+        context.cluster.iter().copied().fold(context.id, char::min)
+    }
+}
+
+impl Default for LeaderConsensus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Consensus for LeaderConsensus {
+    fn propose(&mut self, context: &ConsensusContext, value: MessageContent) -> Vec<Outgoing> {
+        if Self::leader(context) != context.id {
+            return Vec::new();
+        }
+        let confirm = Message::new(
+            context.id,
+            MessageType::Confirm,
+            context.id as u32,
+            value,
+        );
+        self.confirmed = Some(confirm);
+        self.decision = Some(confirm);
+        vec![(confirm, context.cluster.clone())]
+    }
+
+    fn receive(&mut self, context: &ConsensusContext, message: Message) -> Vec<Outgoing> {
+        if message.msg_type == MessageType::Confirm && message.sender_id == Self::leader(context) {
+            self.decision = Some(message);
+        }
+        Vec::new()
+    }
+
+    fn tick(&mut self, _context: &ConsensusContext) -> Vec<Outgoing> {
+        Vec::new()
+    }
+
+    // The leader repeats its decision, followers give up on a silent leader
+    fn on_timeout(&mut self, context: &ConsensusContext) -> Option<Vec<Outgoing>> {
+        self.confirmed.map(|confirm| vec![(confirm, context.cluster.clone())])
+    }
+
+    fn take_decision(&mut self) -> Option<Message> {
+        self.decision.take()
+    }
+
+    fn reset(&mut self) {
+        self.confirmed = None;
+        self.decision = None;
+    }
+}
@@ -0,0 +1,60 @@
+pub mod leader;
+pub mod paxos;
+
+use crate::communication::message::{Message, MessageContent};
+use crate::robot::Team;
+use crate::robot::consensus::leader::LeaderConsensus;
+use crate::robot::consensus::paxos::PaxosConsensus;
+
+// Messages to send and who to send them to
+pub type Outgoing = (Message, Vec<char>);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConsensusKind {
+    Paxos,
+    // The lowest id in the cluster decides alone
+    Leader,
+}
+
+impl ConsensusKind {
+    pub fn build(&self, id: char, max_proposal_retries: u32, retry_backoff_turns: u32) -> Box<dyn Consensus> {
+        match self {
+            ConsensusKind::Paxos => Box::new(PaxosConsensus::new(id, max_proposal_retries, retry_backoff_turns)),
+            ConsensusKind::Leader => Box::new(LeaderConsensus::new()),
+        }
+    }
+
+    // Whether a decided value has to be backed by a majority of Accepted votes
+    pub fn requires_majority(&self) -> bool {
+        match self {
+            ConsensusKind::Paxos => true,
+            ConsensusKind::Leader => false,
+        }
+    }
+}
+
+pub struct ConsensusContext {
+    pub id: char,
+    pub team: Team,
+    pub cluster: Vec<char>,
+    // Turn of the robot and turn of the message board
+    pub turn: usize,
+    pub current_turn: u32,
+}
+
+pub trait Consensus {
+    fn propose(&mut self, context: &ConsensusContext, value: MessageContent) -> Vec<Outgoing>;
+
+    fn receive(&mut self, context: &ConsensusContext, message: Message) -> Vec<Outgoing>;
+
+    // Called every turn while the robot is still deciding
+    fn tick(&mut self, context: &ConsensusContext) -> Vec<Outgoing>;
+
+    // None when the implementation cannot recover by itself and the robot should fall back
+    fn on_timeout(&mut self, context: &ConsensusContext) -> Option<Vec<Outgoing>>;
+
+    // The message carrying the decided value, handed out once
+    fn take_decision(&mut self) -> Option<Message>;
+
+    fn reset(&mut self);
+}
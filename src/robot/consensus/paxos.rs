@@ -0,0 +1,349 @@
+use std::collections::HashSet;
+use colored::Colorize;
+use crate::communication::ballot::Ballot;
+use crate::communication::message::{Message, MessageContent, MessageType};
+use crate::robot::consensus::{Consensus, ConsensusContext, Outgoing};
+
+pub struct PaxosConsensus {
+    // Acceptor
+    promised_message: Option<Message>,
+    accepted: bool,
+
+    // Proposer
+    proposal: Option<Message>,
+    max_id_seen: u32,
+    max_piggyback_id_seen: u32,
+    // Teammates that promised or accepted the current ballot, duplicates and repeated votes count once
+    promisers: HashSet<char>,
+    piggybacked: bool,
+    reached_majority: bool,
+    acceptors: HashSet<char>,
+    majority: u8,
+    ballot: Ballot,
+
+    // Retries
+    retry_at_turn: Option<usize>,
+    proposal_retries: u32,
+    max_proposal_retries: u32,
+    retry_backoff_turns: u32,
+
+    decision: Option<Message>,
+    // Set once a value is decided, later votes and confirms for the round are ignored
+    decided: bool,
+}
+
+// Constructor
+impl PaxosConsensus {
+    pub fn new(id: char, max_proposal_retries: u32, retry_backoff_turns: u32) -> PaxosConsensus {
+        Self {
+            promised_message: None,
+            accepted: false,
+            proposal: None,
+            max_id_seen: 0,
+            max_piggyback_id_seen: 0,
+            promisers: HashSet::new(),
+            piggybacked: false,
+            reached_majority: false,
+            acceptors: HashSet::new(),
+            majority: 0,
+            ballot: Ballot::new(0, id),
+            retry_at_turn: None,
+            proposal_retries: 0,
+            max_proposal_retries,
+            retry_backoff_turns,
+            decision: None,
+            decided: false,
+        }
+    }
+}
+
+impl Consensus for PaxosConsensus {
+    fn propose(&mut self, context: &ConsensusContext, value: MessageContent) -> Vec<Outgoing> {
+        let proposal = Message::new(
+            context.id,
+            MessageType::PrepareRequest,
+            self.ballot.to_id(),
+            value,
+        );
+        self.proposal = Some(proposal);
+        self.majority = (context.cluster.len() / 2) as u8;
+        vec![(proposal, context.cluster.clone())]
+    }
+
+    fn receive(&mut self, context: &ConsensusContext, message: Message) -> Vec<Outgoing> {
+        let mut outgoing = Vec::new();
+        match message.msg_type {
+            MessageType::PrepareRequest => {
+                match self.promised_message {
+                    Some(promised_message) => {
+                        if promised_message.id < message.id {
+                            println!("Robot {} Piggybacked", context.team.style(context.id.to_string()));
+                            self.promised_message = Some(Message::new(
+                                promised_message.sender_id,
+                                promised_message.msg_type,
+                                message.id,
+                                promised_message.message_content,
+                            ));
+                            println!("{:?}", self.promised_message);
+                            let piggyback_msg = Message::new(
+                                context.id,
+                                MessageType::PrepareResponse,
+                                promised_message.id,
+                                promised_message.message_content,
+                            ).with_ballot(message.id);
+                            outgoing.push((piggyback_msg, vec![message.sender_id]));
+                        } else if promised_message.id > message.id {
+                            outgoing.push(Self::nack(context, promised_message, message.sender_id));
+                        }
+                    },
+                    None => {
+                        self.promised_message = Some(message);
+                        let promised = Message::new(
+                            context.id,
+                            MessageType::PrepareResponse,
+                            message.id,
+                            message.message_content,
+                        ).with_ballot(message.id);
+                        outgoing.push((promised, vec![message.sender_id]));
+                    }
+                }
+            },
+            MessageType::AcceptRequest => {
+                if let Some(promised_message) = self.promised_message {
+                    println!("Promised Message: {:?}", promised_message);
+                    println!("Received Message: {:?}", message);
+                    if promised_message.id < message.id || (promised_message.id == message.id && !self.accepted) {
+                        self.accepted = true;
+                        self.promised_message = Some(message);
+                        let accepted_msg = Message::new(
+                            context.id,
+                            MessageType::Accepted,
+                            message.id,
+                            message.message_content,
+                        );
+                        outgoing.push((accepted_msg, vec![message.sender_id]));
+                    } else if promised_message.id > message.id {
+                        outgoing.push(Self::nack(context, promised_message, message.sender_id));
+                    }
+                }
+            },
+            MessageType::PrepareResponse => {
+                // Only promises for the current ballot count, earlier ones answer an abandoned proposal
+                let Some(proposal) = self.proposal else {
+                    return outgoing;
+                };
+                if message.ballot != self.ballot.to_id() || !self.promisers.insert(message.sender_id) {
+                    return outgoing;
+                }
+                if message.id == proposal.id && !self.piggybacked {
+                    if self.promisers.len() > self.majority as usize && !self.reached_majority {
+                        self.reached_majority = true;
+                        println!("Robot {} has received majority promises", context.team.style(context.id.to_string()));
+                        let accept_request_msg = Message::new(
+                            context.id,
+                            MessageType::AcceptRequest,
+                            proposal.id,
+                            proposal.message_content,
+                        );
+                        outgoing.push((accept_request_msg, context.cluster.clone()));
+                    }
+                } else {
+                    self.piggybacked = true;
+                    // Update highset piggyback ID
+                    if message.id > self.max_piggyback_id_seen {
+                        self.max_piggyback_id_seen = message.id;
+                        self.proposal = Some(Message::new(
+                            context.id,
+                            MessageType::AcceptRequest,
+                            proposal.id,
+                            message.message_content,
+                        ));
+                    }
+                    // Check majority
+                    if self.promisers.len() > self.majority as usize && !self.reached_majority {
+                        self.reached_majority = true;
+                        println!("Robot {} has received majority promises", context.team.style(context.id.to_string()));
+                        outgoing.push((self.proposal.unwrap(), context.cluster.clone()));
+                    }
+                }
+            },
+            MessageType::Accepted if message.id == self.ballot.to_id() && !self.decided => {
+                if !self.acceptors.insert(message.sender_id) {
+                    return outgoing;
+                }
+                if self.acceptors.len() > self.majority as usize {
+                    self.decided = true;
+                    self.decision = Some(message);
+                    self.promised_message = Some(message);
+                    outgoing.push((Message::new(
+                        context.id,
+                        MessageType::Confirm,
+                        context.id as u32,
+                        message.message_content,
+                    ), context.cluster.clone()));
+                }
+            },
+            MessageType::Confirm if !self.decided => {
+                self.decided = true;
+                self.decision = Some(message);
+            },
+            MessageType::Nack => {
+                let promised_ballot = Ballot::from_id(message.id);
+                if promised_ballot > self.ballot && self.retry_at_turn.is_none() {
+                    println!("Robot {} proposal {:?} was rejected in favour of {:?}", context.team.style(context.id.to_string()), self.ballot, promised_ballot);
+                    self.max_id_seen = self.max_id_seen.max(message.id);
+                    if self.proposal_retries < self.max_proposal_retries {
+                        let backoff = rand::random_range(1..=self.retry_backoff_turns << self.proposal_retries);
+                        self.retry_at_turn = Some(context.turn + backoff as usize);
+                    }
+                }
+            },
+            _ => {}
+        }
+        outgoing
+    }
+
+    fn tick(&mut self, context: &ConsensusContext) -> Vec<Outgoing> {
+        if self.retry_at_turn.is_some_and(|retry_at_turn| context.turn >= retry_at_turn) {
+            self.retry_proposal(context)
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn on_timeout(&mut self, context: &ConsensusContext) -> Option<Vec<Outgoing>> {
+        if self.proposal.is_some() && self.proposal_retries < self.max_proposal_retries {
+            Some(self.retry_proposal(context))
+        } else {
+            None
+        }
+    }
+
+    fn take_decision(&mut self) -> Option<Message> {
+        self.decision.take()
+    }
+
+    fn reset(&mut self) {
+        let proposer_id = self.ballot.proposer_id;
+        self.promised_message = None;
+        self.accepted = false;
+        self.proposal = None;
+        self.max_id_seen = 0;
+        self.max_piggyback_id_seen = 0;
+        self.promisers.clear();
+        self.piggybacked = false;
+        self.reached_majority = false;
+        self.acceptors.clear();
+        self.majority = 0;
+        self.ballot = Ballot::new(0, proposer_id);
+        self.retry_at_turn = None;
+        self.proposal_retries = 0;
+        self.decision = None;
+        self.decided = false;
+    }
+}
+
+// Proposer logic
+impl PaxosConsensus {
+    fn nack(context: &ConsensusContext, promised_message: Message, proposer_id: char) -> Outgoing {
+        (Message::new(
+            context.id,
+            MessageType::Nack,
+            promised_message.id,
+            promised_message.message_content,
+        ), vec![proposer_id])
+    }
+
+    // Prepare again with a ballot above every one we were rejected with, keeping the current value
+    fn retry_proposal(&mut self, context: &ConsensusContext) -> Vec<Outgoing> {
+        self.retry_at_turn = None;
+        let Some(proposal) = self.proposal else {
+            return Vec::new();
+        };
+        self.proposal_retries += 1;
+        self.ballot = Ballot::from_id(self.max_id_seen).outbid(context.id);
+        self.promisers.clear();
+        self.acceptors.clear();
+        self.piggybacked = false;
+        self.reached_majority = false;
+        self.max_piggyback_id_seen = 0;
+        let new_proposal = Message::new(
+            context.id,
+            MessageType::PrepareRequest,
+            self.ballot.to_id(),
+            proposal.message_content,
+        );
+        println!("Robot {} retries with {:?} (attempt {})", context.team.style(context.id.to_string()).bold(), self.ballot, self.proposal_retries);
+        self.proposal = Some(new_proposal);
+        vec![(new_proposal, context.cluster.clone())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::robot::Team;
+
+    fn pair() -> MessageContent {
+        MessageContent::group(&['B', 'C'])
+    }
+
+    fn context() -> ConsensusContext {
+        ConsensusContext { id: 'A', team: Team::Red, cluster: vec!['B', 'C', 'D', 'E'], turn: 0, current_turn: 0 }
+    }
+
+    fn vote(sender_id: char, msg_type: MessageType, ballot: u32) -> Message {
+        Message::new(sender_id, msg_type, ballot, pair()).with_ballot(ballot)
+    }
+
+    fn sent(outgoing: &[Outgoing], msg_type: MessageType) -> usize {
+        outgoing.iter().filter(|(message, _)| message.msg_type == msg_type).count()
+    }
+
+    #[test]
+    fn repeated_promises_do_not_make_a_majority() {
+        let context = context();
+        let mut paxos = PaxosConsensus::new('A', 0, 1);
+        let ballot = paxos.propose(&context, pair())[0].0.id;
+        let mut outgoing = Vec::new();
+        for _ in 0..3 {
+            outgoing.extend(paxos.receive(&context, vote('B', MessageType::PrepareResponse, ballot)));
+        }
+        outgoing.extend(paxos.receive(&context, vote('C', MessageType::PrepareResponse, ballot)));
+        assert_eq!(sent(&outgoing, MessageType::AcceptRequest), 0);
+        outgoing.extend(paxos.receive(&context, vote('D', MessageType::PrepareResponse, ballot)));
+        outgoing.extend(paxos.receive(&context, vote('E', MessageType::PrepareResponse, ballot)));
+        assert_eq!(sent(&outgoing, MessageType::AcceptRequest), 1);
+    }
+
+    #[test]
+    fn decides_once_on_distinct_accepts() {
+        let context = context();
+        let mut paxos = PaxosConsensus::new('A', 0, 1);
+        let ballot = paxos.propose(&context, pair())[0].0.id;
+        let mut outgoing = Vec::new();
+        for sender_id in ['B', 'B', 'C', 'C'] {
+            outgoing.extend(paxos.receive(&context, vote(sender_id, MessageType::Accepted, ballot)));
+        }
+        assert_eq!(sent(&outgoing, MessageType::Confirm), 0);
+        assert!(paxos.take_decision().is_none());
+        for sender_id in ['D', 'E', 'D'] {
+            outgoing.extend(paxos.receive(&context, vote(sender_id, MessageType::Accepted, ballot)));
+        }
+        outgoing.extend(paxos.receive(&context, Message::new('B', MessageType::Confirm, 'B' as u32, pair())));
+        assert_eq!(sent(&outgoing, MessageType::Confirm), 1);
+        assert!(paxos.take_decision().is_some());
+        assert!(paxos.take_decision().is_none());
+    }
+
+    #[test]
+    fn reset_allows_a_new_decision() {
+        let context = context();
+        let mut paxos = PaxosConsensus::new('A', 0, 1);
+        paxos.receive(&context, Message::new('B', MessageType::Confirm, 'B' as u32, pair()));
+        assert!(paxos.take_decision().is_some());
+        paxos.reset();
+        paxos.receive(&context, Message::new('B', MessageType::Confirm, 'B' as u32, pair()));
+        assert!(paxos.take_decision().is_some());
+    }
+}
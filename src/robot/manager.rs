@@ -1,7 +1,10 @@
 use std::collections::{HashMap};
 use std::sync::{Arc, Mutex};
-use crate::communication::message::MessageBoard;
-use crate::robot::{Robot, Team};
+use crate::communication::message::{Message, MessageBoard};
+use crate::communication::range::RangeModel;
+use crate::util::Coord;
+use crate::robot::{Action, ActionOutcome, Robot, Team};
+use crate::config::consensus::ConsensusConfig;
 
 
 pub struct RobotManager {
@@ -20,8 +23,11 @@ impl RobotManager {
         }
     }
 
+    // Sorted by id so every turn is played in the same order
     pub fn get_robots(&mut self) -> Vec<&mut Robot> {
-        self.robots.values_mut().collect()
+        let mut robots: Vec<&mut Robot> = self.robots.values_mut().collect();
+        robots.sort_by_key(|robot| robot.get_id());
+        robots
     }
 
     pub fn get_robot_by_id(&mut self, id: char) -> Option<&mut Robot> {
@@ -45,19 +51,22 @@ impl RobotManager {
 
 // Robot Actions Logic
 impl RobotManager {
-    pub fn pickup_gold(&mut self, id_1: char, id_2: char) -> bool {
-        let robot_1 = self.get_robot_by_id(id_1).unwrap();
-        if robot_1.is_carrying {
-            return false;
+    pub fn report_outcome(&mut self, ids: &[char], outcome: ActionOutcome) {
+        for id in ids {
+            self.get_robot_by_id(*id).unwrap().report_outcome(outcome);
         }
-        let robot_2 = self.get_robot_by_id(id_2).unwrap();
-        if robot_2.is_carrying {
-            return false;
+    }
+
+    pub fn is_any_carrying(&self, ids: &[char]) -> bool {
+        ids.iter().any(|id| self.robots[id].is_carrying)
+    }
+
+    // Every robot of the group carries the bar together
+    pub fn pickup_gold(&mut self, ids: &[char], value: u8) {
+        for id in ids {
+            let carrying_group: Vec<char> = ids.iter().copied().filter(|other_id| other_id != id).collect();
+            self.get_robot_by_id(*id).unwrap().pickup(carrying_group, value);
         }
-        robot_2.pickup(id_1);
-        let robot_1 = self.get_robot_by_id(id_1).unwrap();
-        robot_1.pickup(id_2);
-        true
     }
 
 }
@@ -68,6 +77,74 @@ impl RobotManager {
         let mut message_board_guard = self.message_board.lock().unwrap();
         message_board_guard.update();
     }
+
+    pub fn take_broadcasts(&mut self) -> Vec<(Coord, Message)> {
+        self.message_board.lock().unwrap().get_interference().take_broadcasts()
+    }
+
+    // Robots within eavesdropping radius of an enemy sender hear its message
+    pub fn overhear(&mut self, broadcasts: Vec<(Coord, Message)>) {
+        let eavesdrop_radius = self.message_board.lock().unwrap().get_interference().get_eavesdrop_radius();
+        let Some(eavesdrop_radius) = eavesdrop_radius else {
+            return;
+        };
+        for robot in self.robots.values_mut() {
+            if robot.is_crashed() {
+                continue;
+            }
+            for (sender_coord, message) in broadcasts.iter() {
+                if RangeModel::within_radius(*sender_coord, robot.get_coord(), eavesdrop_radius) {
+                    robot.overhear(*message);
+                }
+            }
+        }
+    }
+
+    pub fn get_jammer_coords(&self) -> Vec<Coord> {
+        self.robots.values()
+            .filter(|robot| !robot.is_crashed() && robot.get_latest_action() == Action::Jam)
+            .map(|robot| robot.get_coord())
+            .collect()
+    }
+
+    pub fn get_tacklers(&self) -> Vec<(char, Coord)> {
+        let mut tacklers: Vec<(char, Coord)> = self.robots.values()
+            .filter(|robot| !robot.is_crashed() && robot.get_latest_action() == Action::Tackle)
+            .map(|robot| (robot.get_id(), robot.get_coord()))
+            .collect();
+        tacklers.sort_by_key(|(id, _)| *id);
+        tacklers
+    }
+
+    pub fn record_drops(&self, voluntary_drops: u32, low_battery_put_downs: u32, fumbles: u32) {
+        let mut message_board_guard = self.message_board.lock().unwrap();
+        let metrics = message_board_guard.get_metrics_mut();
+        metrics.voluntary_drops += voluntary_drops;
+        metrics.low_battery_put_downs += low_battery_put_downs;
+        metrics.fumbles += fumbles;
+    }
+
+    pub fn record_score(&self, value: u32, with_byzantine: bool) {
+        self.message_board.lock().unwrap().get_metrics_mut().record_score(value, with_byzantine);
+    }
+
+    pub fn record_tackles(&self, tackles: u32, successful_tackles: u32) {
+        let mut message_board_guard = self.message_board.lock().unwrap();
+        let metrics = message_board_guard.get_metrics_mut();
+        metrics.tackles += tackles;
+        metrics.successful_tackles += successful_tackles;
+    }
+
+    pub fn set_jammers(&mut self, jammers: Vec<Coord>) {
+        self.message_board.lock().unwrap().get_interference().set_jammers(jammers);
+    }
+
+    pub fn update_positions(&mut self) {
+        let mut message_board_guard = self.message_board.lock().unwrap();
+        for robot in self.robots.values() {
+            message_board_guard.set_position(robot.get_id(), robot.get_coord());
+        }
+    }
 }
 
 // Print Functions
@@ -84,6 +161,51 @@ impl RobotManager {
             }
         }
     }
+    pub fn print_metrics(&self) {
+        let name = match self.team {
+            Team::Blue => "BLU",
+            Team::Red => "RED",
+        };
+        println!("{} Metrics ({:?} consensus)", self.team.style(name.to_string()), ConsensusConfig::new(self.team).kind);
+        println!("{}", self.message_board.lock().unwrap().get_metrics());
+    }
+
+    pub fn print_outcomes(&self) {
+        let mut ids: Vec<&char> = self.robots.keys().collect();
+        ids.sort();
+        for id in ids {
+            if let Some(outcome) = self.robots[id].get_latest_outcome() {
+                println!("Robot {} outcome: {:?}", self.team.style(id.to_string()), outcome);
+            }
+        }
+    }
+
+    pub fn print_safety_report(&self) {
+        let name = match self.team {
+            Team::Blue => "BLU",
+            Team::Red => "RED",
+        };
+        let message_board_guard = self.message_board.lock().unwrap();
+        match message_board_guard.get_checker() {
+            Some(checker) => {
+                println!("{} Safety: {} violations", self.team.style(name.to_string()), checker.get_violations().len());
+                for violation in checker.get_violations() {
+                    println!("  {:?}", violation);
+                }
+            },
+            None => println!("{} Safety: checker disabled", self.team.style(name.to_string())),
+        }
+    }
+
+    pub fn print_partition_report(&self) {
+        let name = match self.team {
+            Team::Blue => "BLU",
+            Team::Red => "RED",
+        };
+        println!("{} Partitions", self.team.style(name.to_string()));
+        println!("{}", self.message_board.lock().unwrap().get_partition_schedule());
+    }
+
     pub fn print_message_board_debug(&self) {
         match self.team {
             Team::Blue => {
@@ -1,22 +1,33 @@
+pub mod consensus;
 pub mod manager;
 
-use std::collections::{LinkedList, HashMap};
+use std::collections::{LinkedList, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::sync::{Arc, Mutex};
 use std::io;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use crate::util::Coord;
 use colored::{ColoredString, Colorize};
-use rand::Rng;
-use crate::communication::message::{Message, MessageBoard, MessageContent, MessageType};
+use crate::communication::auth::MacKey;
+use crate::communication::message::{Message, MessageBoard, MessageContent, MessageType, Observation, MAX_GROUP_SIZE, MAX_OBSERVATIONS};
 use crate::config::logger::LoggerConfig;
 use crate::environment::cell::Cell;
 use crate::environment::grid::Grid;
 use crate::config::Config;
+use crate::config::deposit::BoxRules;
+use crate::config::energy::EnergyConfig;
+use crate::config::pickup::{PickupConfig, RobotsRequired};
+use crate::config::tackle::TackleConfig;
+use crate::config::timeout::TimeoutConfig;
+use crate::config::consensus::ConsensusConfig;
+use crate::config::network::NetworkConfig;
+use crate::config::fault::{ByzantineBehaviour, FaultConfig};
+use crate::robot::consensus::{Consensus, ConsensusContext, Outgoing};
 
 use rand::seq::IndexedRandom;
 use crate::robot::Action::Turn;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum RobotState {
     ClusterFinding,
     Paxos,
@@ -75,6 +86,48 @@ pub enum Action {
     Turn(Direction),
     PickUp,
     Idle,
+    // Disrupts enemy messages around the robot
+    Jam,
+    // Tries to knock the bar out of a nearby enemy carrying group
+    Tackle,
+    // Puts the carried bar down on the current cell, the whole group has to drop together
+    Drop,
+}
+
+// What actually came of a robot's action, settled by the world at the end of the turn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ActionOutcome {
+    Moved,
+    // Walked into the edge of the grid
+    Blocked,
+    // Turned back by a full cell
+    Bounced,
+    Turned,
+    Idled,
+    PickedUp(u8),
+    // Both teams went for the last bar and the contention rule left this team empty handed
+    PickupContention,
+    // The bars were gone by the time the robot picked up
+    PickupNoGold,
+    // Too few or too many teammates on the cell for the bar
+    PickupGroupMismatch,
+    // Someone in the group already carries a bar
+    PickupAlreadyCarrying,
+    Jammed,
+    TackleLanded,
+    TackleMissed,
+    TackleOnCooldown,
+    // The group dropped its bar at this cell
+    Fumbled(Coord),
+    // The group put its bar down at this cell on purpose
+    Dropped(Coord),
+    // The group put its bar down at this cell as one of them could not carry it home
+    PutDownLowBattery(Coord),
+    // Nothing carried, or a deposit box where bars can only be delivered
+    DropRefused,
+    Scored(u8),
+    // The deposit box was closed or full
+    DeliveryRefused,
 }
 
 impl Debug for Action {
@@ -84,6 +137,9 @@ impl Debug for Action {
             Action::Turn(direction) => write!(f, "{} to {:?}", "TURN".red().bold(), direction),
             Action::PickUp => write!(f, "{}", "PICK UP".yellow().bold()),
             Action::Idle => write!(f, "{}", "IDLE".blue().bold()),
+            Action::Jam => write!(f, "{}", "JAM".magenta().bold()),
+            Action::Tackle => write!(f, "{}", "TACKLE".magenta().bold()),
+            Action::Drop => write!(f, "{}", "DROP".yellow().bold()),
         }
     }
 }
@@ -96,51 +152,67 @@ pub struct Robot {
     facing: Direction,
     is_carrying: bool,
     was_carrying: bool,
-    pair_id: Option<char>,
+    // Teammates carrying the same bar and what it is worth
+    carrying_group: Vec<char>,
+    carried_value: u8,
+    // Boxes that turned the carried bar away, the whole group hears of it on the same turn
+    refused_boxes: Vec<Coord>,
     coord_history: Vec<Coord>,
     action_history: Vec<Action>,
+    outcome_history: Vec<ActionOutcome>,
     turn: usize,
-    deposit_box_coord: Coord,
+    deposit_boxes: Vec<Coord>,
+    deposit_rules: HashMap<Coord, BoxRules>,
 
     // Perception
     observable_cells: LinkedList<Coord>,
     knowledge_base: HashMap<Coord, Cell>,
 
+    // Knowledge Sharing
+    teammate_ids: Vec<char>,
+    kb_sync: bool,
+    kb_sync_max_cells: usize,
+    knowledge_turns: HashMap<Coord, u32>,
+    self_observed: HashSet<Coord>,
+    kb_sync_sent: u32,
+    kb_sync_merged: u32,
+
     // Communication
     message_board: Arc<Mutex<MessageBoard>>,
+    eavesdropping: bool,
+    jamming: bool,
+    auth_key: Option<MacKey>,
     message_to_send: Option<Message>,
+    epoch: u32,
+    message_ttl: Option<u32>,
 
     // Local Cluster Identification
     receiver_ids: Vec<char>,
     target_gold: Option<Coord>,
     old_target_gold: Option<Coord>,
-    target_gold_amount: u8,
-    max_gold_seen: u8,
+    target_gold_amount: u16,
+    max_priority_seen: f64,
+    enemy_penalty: f64,
+    // How the world decides the group size of a bar
+    robots_required: RobotsRequired,
     send_target: bool,
     local_cluster: Vec<char>,
-    clusters: HashMap<(Coord, u8), Vec<char>>,
+    clusters: HashMap<(Coord, u8, u16), Vec<char>>,
     not_received_simple: u8,
 
     // Backup Cluster
-    max_gold_receive: u8,
+    max_priority_receive: f64,
     max_gold_receive_coord: Option<Coord>,
     backup_cluster: Vec<char>,
 
     // PAXOS
+    consensus: Box<dyn Consensus>,
+    consensus_started_turn: usize,
     consensus_coord: Option<Coord>,
-    promised_message: Option<Message>,
-    max_id_seen: u32,
-    max_piggyback_id_seen: u32,
-    promise_count: u8,
-    piggybacked: bool,
-    reached_majority: bool,
-    accept_count: u8,
-    majority: u8,
-    increment: u32,
-    send_pair_request: bool,
-    consensus_pair: Option<(char, char)>,
-    pre_pickup_pair_id: Option<char>,
-    accepted: bool,
+    send_group_request: bool,
+    consensus_group: Vec<char>,
+    // Teammates of the decided group, empty when we are not in it
+    pre_pickup_group: Vec<char>,
 
     // Direction Consensus
     sent_direction_request: bool,
@@ -155,17 +227,35 @@ pub struct Robot {
     received_begin: bool,
 
     // Resolve across cluster
-    combined_pair_id: Option<u32>,
+    // Highest id of our group, the group with the higher one keeps a contested cell
+    group_id: Option<u32>,
     send_getout: bool,
     override_target_gold: bool,
 
     // React to gold getting nabbed
     is_second_check: bool,
 
-    carrying_with_wrong_pair: bool,
+    carrying_with_wrong_group: bool,
+
+    // Fault Injection
+    crashed: bool,
+    byzantine: Vec<ByzantineBehaviour>,
+
+    // Tackling
+    tackle_config: TackleConfig,
+    tackle_ready_turn: usize,
+    tackled: bool,
+
+    // Energy
+    battery: u32,
+    recharging: bool,
+    energy_config: EnergyConfig,
 
     // State Tracking
     current_state: RobotState,
+    last_state: RobotState,
+    waiting_since: usize,
+    timeout_config: TimeoutConfig,
 
     // Configurations
     logger_config: LoggerConfig,
@@ -173,10 +263,16 @@ pub struct Robot {
 
 // Constructors and getters
 impl Robot {
-    pub fn new(id: char, team: Team, current_coord:Coord, facing: Direction, message_board: Arc<Mutex<MessageBoard>>, deposit_box_coord: Coord) -> Self {
+    pub fn new(id: char, team: Team, current_coord:Coord, facing: Direction, message_board: Arc<Mutex<MessageBoard>>, deposit_boxes: Vec<Coord>, deposit_rules: HashMap<Coord, BoxRules>) -> Self {
         let mut coord_history: Vec<Coord> = Vec::new();
-        let Config { n_robots, .. } = Config::new();
+        let Config { n_robots, enemy_penalty, kb_sync, kb_sync_max_cells, message_ttl, max_proposal_retries, retry_backoff_turns, .. } = Config::new();
         coord_history.push(current_coord);
+        let byzantine = FaultConfig::new().byzantine_behaviours(id);
+        let network_config = NetworkConfig::new(team);
+        let energy_config = EnergyConfig::new();
+        if !byzantine.is_empty() {
+            message_board.lock().unwrap().get_metrics_mut().byzantine_robots += 1;
+        }
         Robot {
             // General
             id,
@@ -185,56 +281,68 @@ impl Robot {
             facing,
             is_carrying: false,
             was_carrying: false,
-            pair_id: None,
+            carrying_group: Vec::new(),
+            carried_value: 0,
+            refused_boxes: Vec::new(),
             coord_history,
             action_history: Vec::new(),
+            outcome_history: Vec::new(),
             turn: 0,
-            deposit_box_coord,
+            deposit_boxes,
+            deposit_rules,
 
             // Perception
             observable_cells: LinkedList::new(),
             knowledge_base: HashMap::new(),
 
+            // Knowledge Sharing
+            teammate_ids: make_vec(n_robots, id, team),
+            kb_sync,
+            kb_sync_max_cells,
+            knowledge_turns: HashMap::new(),
+            self_observed: HashSet::new(),
+            kb_sync_sent: 0,
+            kb_sync_merged: 0,
+
             // Communication
             message_board,
+            eavesdropping: network_config.eavesdrop_radius.is_some(),
+            jamming: network_config.jamming,
+            auth_key: network_config.auth_key,
             message_to_send: Some(Message::new(
                 id,
                 MessageType::PrepareRequest,
                 id as u32,
                 MessageContent::Coord(Some(current_coord), Some(0)),
             )),
+            epoch: 0,
+            message_ttl,
 
             // Local Cluster Identification
             receiver_ids: make_vec(n_robots, id, team),
             target_gold: None,
             old_target_gold: None,
             target_gold_amount: 0,
-            max_gold_seen: 0,
+            max_priority_seen: f64::NEG_INFINITY,
+            enemy_penalty,
+            robots_required: PickupConfig::new().robots_required,
             send_target: false,
             local_cluster: Vec::new(),
             clusters: HashMap::new(),
             not_received_simple: n_robots - 1,
 
             // Backup Cluster
-            max_gold_receive: 0,
+            max_priority_receive: f64::NEG_INFINITY,
             max_gold_receive_coord: None,
             backup_cluster: Vec::new(),
 
             // PAXOS
+            consensus: ConsensusConfig::new(team).kind.build(id, max_proposal_retries, retry_backoff_turns),
+            consensus_started_turn: 0,
             consensus_coord: None,
-            promised_message: None,
-            max_id_seen: 0,
-            max_piggyback_id_seen: 0,
-            promise_count: 0,
-            piggybacked: false,
-            reached_majority: false,
-            accept_count: 0,
-            majority: n_robots / 2,
-            increment: id as u32,
-            send_pair_request: false,
-            consensus_pair: None,
-            pre_pickup_pair_id: None,
-            accepted: false,
+            send_group_request: false,
+            consensus_group: Vec::new(),
+            pre_pickup_group: Vec::new(),
 
             // Direction Consensus
             sent_direction_request: false,
@@ -249,17 +357,34 @@ impl Robot {
             received_begin: true,
 
             // Resolve across cluster
-            combined_pair_id: None,
+            group_id: None,
             send_getout: false,
             override_target_gold: false,
 
             // React to gold getting nabbed
             is_second_check: false,
 
-            carrying_with_wrong_pair: false,
+            carrying_with_wrong_group: false,
+
+            // Fault Injection
+            crashed: false,
+            byzantine,
+
+            // Tackling
+            tackle_config: TackleConfig::new(team),
+            tackle_ready_turn: 0,
+            tackled: false,
+
+            // Energy
+            battery: energy_config.capacity,
+            recharging: false,
+            energy_config,
 
             // State Tracking
             current_state: RobotState::ClusterFinding,
+            last_state: RobotState::ClusterFinding,
+            waiting_since: 0,
+            timeout_config: TimeoutConfig::new(),
 
             // Configuration
             logger_config: LoggerConfig::new(),
@@ -268,16 +393,19 @@ impl Robot {
 
     pub fn reset(&mut self) {
 
+        // Communication
+        self.epoch = self.message_board.lock().unwrap().next_round(self.epoch);
+
         // General
         self.is_carrying = false;
         self.was_carrying = false;
-        self.pair_id = None;
+        self.carrying_group.clear();
 
         // Local Cluster Identification
         self.target_gold = None;
         self.old_target_gold = None;
         self.target_gold_amount = 0;
-        self.max_gold_seen = 0;
+        self.max_priority_seen = f64::NEG_INFINITY;
         self.send_target = false;
         self.clusters = HashMap::new();
         self.not_received_simple = self.receiver_ids.len() as u8;
@@ -285,26 +413,16 @@ impl Robot {
         println!("Robot {}: New Global contains {} robots", self.team.style(self.id.to_string()).bold(), self.not_received_simple);
 
         // Backup Cluster
-        self.max_gold_receive = 0;
+        self.max_priority_receive = f64::NEG_INFINITY;
         self.max_gold_receive_coord = None;
         self.backup_cluster = Vec::new();
 
         // PAXOS
+        self.consensus.reset();
         self.consensus_coord = None;
-        self.promised_message = None;
-        self.max_id_seen = 0;
-        self.max_piggyback_id_seen = 0;
-        self.promise_count = 0;
-        self.piggybacked = false;
-        self.reached_majority = false;
-        self.accept_count = 0;
-        // self.majority = (self.local_cluster.len() / 2) as u8;
-        self.majority = (self.not_received_simple / 2) as u8;
-        self.increment = self.id as u32;
-        self.send_pair_request = false;
-        self.consensus_pair = None;
-        self.pre_pickup_pair_id = None;
-        self.accepted = false;
+        self.send_group_request = false;
+        self.consensus_group.clear();
+        self.pre_pickup_group.clear();
 
         // Direction Consensus
         self.sent_direction_request = false;
@@ -312,11 +430,11 @@ impl Robot {
         self.turned = false;
 
         // Resolve across clustser
-        self.combined_pair_id = None;
+        self.group_id = None;
         self.send_getout = false;
         self.override_target_gold = false;
 
-        self.carrying_with_wrong_pair = false;
+        self.carrying_with_wrong_group = false;
 
         // State Tracking
         self.current_state = RobotState::ClusterFinding;
@@ -334,6 +452,63 @@ impl Robot {
         ), self.local_cluster.clone());
     }
 
+    // Stops deciding and reading messages until restarted
+    pub fn crash(&mut self) {
+        self.crashed = true;
+        self.message_board.lock().unwrap().get_metrics_mut().crashes += 1;
+        println!("Robot {} has {}", self.team.style(self.id.to_string()).bold(), "CRASHED".red().bold());
+    }
+
+    // Comes back with an empty knowledge base and fresh protocol state
+    pub fn restart(&mut self) {
+        self.crashed = false;
+        self.knowledge_base.clear();
+        self.knowledge_turns.clear();
+        self.self_observed.clear();
+        self.planned_actions.clear();
+        self.received_begin = true;
+        self.receiver_ids = self.teammate_ids.clone();
+        self.local_cluster.clear();
+        self.reset();
+        self.message_board.lock().unwrap().get_metrics_mut().restarts += 1;
+        println!("Robot {} has {}", self.team.style(self.id.to_string()).bold(), "RESTARTED".green().bold());
+    }
+
+    pub fn is_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.energy_config.enabled && self.battery == 0
+    }
+
+    // The battery no longer covers carrying the bar to the nearest box
+    pub fn is_too_low_to_carry(&self) -> bool {
+        if !self.energy_config.enabled || !self.is_carrying {
+            return false;
+        }
+        let deposit_box = self.nearest_deposit_box();
+        let distance = self.current_coord.x.abs_diff(deposit_box.x) + self.current_coord.y.abs_diff(deposit_box.y);
+        distance > 0 && self.battery <= self.energy_config.trip_cost(distance, true)
+    }
+
+    pub fn get_battery(&self) -> Option<u32> {
+        self.energy_config.enabled.then_some(self.battery)
+    }
+
+    fn is_tackle_ready(&self) -> bool {
+        self.turn >= self.tackle_ready_turn
+    }
+
+    // Knocked by an enemy, the group drops its bar at the fumble check
+    pub fn tackle(&mut self) {
+        self.tackled = true;
+    }
+
+    pub fn is_tackled(&self) -> bool {
+        self.tackled
+    }
+
     pub fn get_team(&self) -> Team {
         self.team
     }
@@ -356,8 +531,20 @@ impl Robot {
     }
 
     pub fn was_carrying(&self) -> bool { self.was_carrying }
-    pub fn get_pair_id(&self) -> Option<char> {
-        self.pair_id
+    pub fn get_carried_value(&self) -> u8 {
+        self.carried_value
+    }
+
+    pub fn get_carrying_group(&self) -> Vec<char> {
+        self.carrying_group.clone()
+    }
+
+    pub fn get_knowledge_coverage(&self) -> (usize, usize) {
+        (self.self_observed.len(), self.knowledge_base.len())
+    }
+
+    pub fn get_kb_sync_counts(&self) -> (u32, u32) {
+        (self.kb_sync_sent, self.kb_sync_merged)
     }
 
 }
@@ -365,25 +552,38 @@ impl Robot {
 // Decision logic 
 impl Robot {
     pub fn make_decision(&mut self, manual: bool) -> Action {
+        self.react_to_outcome();
         if self.is_carrying {
             self.was_carrying = true;
         }
-        if self.not_received_simple == 0 && !self.send_pair_request {
-            let mut rng = rand::rng();
-            let pair_id = self.local_cluster.choose(&mut rng);
-            if pair_id.is_some() {
-                self.message_to_send = Some(Message::new(
-                    self.id,
-                    MessageType::PrepareRequest,
-                    self.id as u32,
-                    MessageContent::Pair(self.id, *pair_id.unwrap())),
-                );
-                self.send(self.message_to_send.unwrap(), self.local_cluster.clone());
-                self.majority = (self.local_cluster.len() / 2) as u8;
-                self.send_pair_request = true;
+        if self.current_state != RobotState::Paxos {
+            self.consensus_started_turn = self.turn;
+        }
+        if self.not_received_simple == 0 && !self.send_group_request && let Some(target) = self.target_gold {
+            // Clusters too small for their bar propose nothing and time out into the next round
+            let group_size = self.robots_required_at(target);
+            if group_size <= MAX_GROUP_SIZE && self.local_cluster.len() + 1 >= group_size {
+                let mut rng = rand::rng();
+                let mut group: Vec<char> = self.local_cluster.choose_multiple(&mut rng, group_size - 1).copied().collect();
+                group.push(self.id);
+                let context = self.consensus_context();
+                let outgoing = self.consensus.propose(&context, MessageContent::group(&group));
+                self.apply_consensus(outgoing);
+                self.send_group_request = true;
             }
         }
-        self.paxos_receiver(self.receive());
+        if self.current_state == RobotState::Paxos {
+            let context = self.consensus_context();
+            let outgoing = self.consensus.tick(&context);
+            self.apply_consensus(outgoing);
+        }
+        for message in self.receive() {
+            self.paxos_receiver(Some(message));
+        }
+        if self.is_byzantine(ByzantineBehaviour::SpamGetOut) {
+            self.spam_getout();
+        }
+        self.check_timeouts();
         if manual {
             let mut input_string = String::new();
             io::stdin().read_line(&mut input_string).expect("Failed to read line");
@@ -393,14 +593,19 @@ impl Robot {
                 "l" => Action::Turn(Direction::Left),
                 "r" => Action::Turn(Direction::Right),
                 "p" => Action::PickUp,
+                "j" => Action::Jam,
+                "t" => Action::Tackle,
+                "x" => Action::Drop,
                 _ => Action:: Move,
             }
+        } else if let Some(action) = self.recharge_action() {
+            action
         } else if !self.planned_actions.is_empty() {
             println!("{:?}", self.planned_actions);
             self.planned_actions.remove(0)
         } else {
             // Spam PICKUP
-            if !self.is_carrying() && self.pre_pickup_pair_id.is_some() && self.turned {
+            if !self.is_carrying() && !self.pre_pickup_group.is_empty() && self.turned {
                 if self.current_coord == self.target_gold.unwrap() {
                     Action::PickUp
                 } else {
@@ -411,17 +616,22 @@ impl Robot {
                     Action::Turn(Direction::Right)
                 }
             } else if self.is_carrying() {
-                if self.pre_pickup_pair_id.unwrap() == self.pair_id.unwrap() {
+                // Bars a robot lifts alone leave it without a group
+                if self.carrying_group.is_empty() || self.pre_pickup_group.iter().all(|id| self.carrying_group.contains(id)) {
                     self.current_state = RobotState::MovingToDropBox;
-                    self.plan_actions_to_move_to(self.deposit_box_coord);
+                    self.plan_actions_to_move_to(self.nearest_deposit_box());
                     Action::Idle
                     // Action::Turn(Direction::Up)
                 } else {
-                    self.carrying_with_wrong_pair = true;
+                    self.carrying_with_wrong_group = true;
                     Action::PickUp
                 }
             } else {
-                if self.current_state != RobotState::ClusterFinding && self.current_state != RobotState::WaitingForTaskCompletion {
+                if self.tackle_config.enabled && self.is_tackle_ready() && self.sees_enemy_carrier() {
+                    Action::Tackle
+                } else if self.jamming && self.current_state == RobotState::WaitingForTaskCompletion && self.sees_enemy() {
+                    Action::Jam
+                } else if self.current_state != RobotState::ClusterFinding && self.current_state != RobotState::WaitingForTaskCompletion {
                     // Turn randomly
                     match rand::random_range(1..5) {
                         1 => Turn(Direction::Left),
@@ -451,29 +661,233 @@ impl Robot {
 
 }
 
+// Outcome logic
+impl Robot {
+    fn react_to_outcome(&mut self) {
+        match self.get_latest_outcome() {
+            // The bar went before we could lift it, no need to wait for a second look
+            Some(ActionOutcome::PickupNoGold) if !self.pre_pickup_group.is_empty() && !self.is_carrying => {
+                self.received_begin = true;
+                self.receiver_ids = self.local_cluster.clone();
+                self.scored();
+                self.local_cluster.clear();
+                self.reset();
+                self.planned_actions.clear();
+                self.is_second_check = false;
+            },
+            // A box that is open but turned us away is full, so the group looks for another one together
+            Some(ActionOutcome::DeliveryRefused) => {
+                let turn = self.message_board.lock().unwrap().get_current_turn();
+                if self.deposit_rules[&self.current_coord].is_open(turn) && !self.refused_boxes.contains(&self.current_coord) {
+                    self.refused_boxes.push(self.current_coord);
+                    self.planned_actions.clear();
+                }
+            },
+            // Walking into the edge means the path was planned from somewhere else, plan it again from here
+            Some(ActionOutcome::Blocked) if !self.planned_actions.is_empty() => {
+                let destination = if self.recharging || self.current_state == RobotState::MovingToDropBox {
+                    Some(self.nearest_deposit_box())
+                } else if self.current_state == RobotState::MovingToTarget {
+                    self.target_gold
+                } else {
+                    None
+                };
+                self.planned_actions.clear();
+                if let Some(destination) = destination {
+                    self.plan_actions_to_move_to(destination);
+                }
+            },
+            // Robots that bounced off each other would keep colliding, so half the time wait a turn first.
+            // The coin is shared by the carrying group to keep its members in step
+            Some(ActionOutcome::Bounced) if !self.planned_actions.is_empty() => {
+                self.planned_actions.insert(0, Action::Move);
+                let leader = self.carrying_group.iter().copied().chain([self.id]).min().unwrap();
+                let mut hasher = DefaultHasher::new();
+                (self.turn, leader).hash(&mut hasher);
+                if hasher.finish().is_multiple_of(2) {
+                    self.planned_actions.insert(0, Action::Idle);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+// Energy logic
+impl Robot {
+    // Charges the action taken this turn, then tops up on the team's deposit boxes
+    fn use_energy(&mut self, action: &Action) {
+        if !self.energy_config.enabled {
+            return;
+        }
+        let had_energy = self.battery > 0;
+        self.battery = self.battery.saturating_sub(self.energy_config.action_cost(action, self.is_carrying));
+        if self.deposit_boxes.contains(&self.current_coord) {
+            self.battery = (self.battery + self.energy_config.recharge_rate).min(self.energy_config.capacity);
+        }
+        if had_energy && self.battery == 0 {
+            self.message_board.lock().unwrap().get_metrics_mut().ran_flat += 1;
+            println!("Robot {} has {}", self.team.style(self.id.to_string()).bold(), "RAN FLAT".red().bold());
+        }
+    }
+
+    // Drops the current task and heads for the nearest box once the battery only covers the trip back,
+    // then waits there until fully charged. Carriers finish their delivery first, the world has the
+    // group put its bar down once one of them can no longer carry it home.
+    fn recharge_action(&mut self) -> Option<Action> {
+        if !self.energy_config.enabled || self.is_carrying {
+            return None;
+        }
+        let deposit_box = self.nearest_deposit_box();
+        let distance = self.current_coord.x.abs_diff(deposit_box.x) + self.current_coord.y.abs_diff(deposit_box.y);
+        if !self.recharging {
+            if self.battery > self.energy_config.trip_cost(distance, false) + self.energy_config.return_margin {
+                return None;
+            }
+            self.recharging = true;
+            self.message_board.lock().unwrap().get_metrics_mut().recharge_trips += 1;
+            println!("Robot {} is low on energy ({}), returning to {:?}", self.team.style(self.id.to_string()).bold(), self.battery, deposit_box);
+            self.received_begin = true;
+            self.receiver_ids = self.local_cluster.clone();
+            self.scored();
+            self.local_cluster.clear();
+            self.reset();
+            self.planned_actions.clear();
+        }
+        if self.deposit_boxes.contains(&self.current_coord) {
+            if self.battery < self.energy_config.capacity {
+                return Some(Action::Idle);
+            }
+            self.recharging = false;
+            return None;
+        }
+        if self.planned_actions.is_empty() {
+            self.plan_actions_to_move_to(deposit_box);
+        }
+        Some(self.planned_actions.remove(0))
+    }
+}
+
+// Timeout logic
+impl Robot {
+    fn check_timeouts(&mut self) {
+        if self.current_state != self.last_state {
+            self.last_state = self.current_state;
+            self.waiting_since = self.turn;
+        }
+        let timeout = match self.current_state {
+            RobotState::ClusterFinding if self.send_target => self.timeout_config.cluster_finding,
+            RobotState::Paxos => self.timeout_config.paxos,
+            RobotState::AtTarget if self.sent_direction_request && !self.turned => self.timeout_config.direction_ack,
+            RobotState::WaitingForTaskCompletion => self.timeout_config.task_completion,
+            _ => None,
+        };
+        if let Some(timeout) = timeout && self.turn - self.waiting_since >= timeout {
+            self.on_timeout();
+            self.waiting_since = self.turn;
+        }
+    }
+
+    fn on_timeout(&mut self) {
+        let state = self.current_state;
+        self.message_board.lock().unwrap().get_metrics_mut().record_timeout(state);
+        let recovery = match state {
+            RobotState::ClusterFinding => {
+                // Go ahead with the reports received so far
+                self.not_received_simple = 0;
+                self.current_state = RobotState::Paxos;
+                if self.cluster_too_small() {
+                    self.merge_small_clusters();
+                }
+                "forming cluster from received reports"
+            },
+            RobotState::Paxos if self.send_group_request && let Some(outgoing) = self.consensus.on_timeout(&self.consensus_context()) => {
+                self.apply_consensus(outgoing);
+                "re-proposing"
+            },
+            RobotState::Paxos if !self.send_group_request => {
+                // Too few of us for the bar, so the next round asks the whole team
+                self.received_begin = true;
+                self.receiver_ids = self.teammate_ids.clone();
+                self.scored();
+                self.local_cluster.clear();
+                self.reset();
+                self.planned_actions.clear();
+                "restarting with the whole team"
+            },
+            RobotState::AtTarget => {
+                // Observation sends a new direction request
+                self.sent_direction_request = false;
+                "resending direction request"
+            },
+            _ => {
+                self.received_begin = true;
+                self.receiver_ids = self.local_cluster.clone();
+                self.scored();
+                self.local_cluster.clear();
+                self.reset();
+                self.planned_actions.clear();
+                "resetting"
+            },
+        };
+        println!("Robot {} timed out in {:?} after {} turns, {}", self.team.style(self.id.to_string()).bold(), state, self.turn - self.waiting_since, recovery);
+    }
+}
+
 // Action logic
 impl Robot {
     pub fn take_action(&mut self, action: &Action, grid: &mut Grid) {
-        match action {
+        let outcome = match action {
             Action::Turn(direction) => {
                 self.turn(*direction);
                 self.action_history.push(Action::Turn(*direction));
                 self.coord_history.push(self.current_coord);
+                ActionOutcome::Turned
             },
             Action::Move => {
-                self.step(grid);
+                let moved = self.step(grid);
                 self.action_history.push(Action::Move);
                 self.coord_history.push(self.current_coord);
+                if moved { ActionOutcome::Moved } else { ActionOutcome::Blocked }
             },
             Action::PickUp => {
                 self.action_history.push(Action::PickUp);
                 self.coord_history.push(self.current_coord);
+                // Settled by the world's pickup check
+                ActionOutcome::PickupNoGold
             },
             Action::Idle => {
                 self.action_history.push(Action::Idle);
                 self.coord_history.push(self.current_coord);
+                ActionOutcome::Idled
+            },
+            Action::Jam => {
+                self.action_history.push(Action::Jam);
+                self.coord_history.push(self.current_coord);
+                ActionOutcome::Jammed
             }
-        }
+            // Tackles during the cooldown do nothing
+            Action::Tackle if self.is_tackle_ready() => {
+                self.tackle_ready_turn = self.turn + self.tackle_config.cooldown as usize;
+                self.action_history.push(Action::Tackle);
+                self.coord_history.push(self.current_coord);
+                // Settled by the world's tackle check
+                ActionOutcome::TackleMissed
+            }
+            Action::Tackle => {
+                self.action_history.push(Action::Idle);
+                self.coord_history.push(self.current_coord);
+                ActionOutcome::TackleOnCooldown
+            }
+            Action::Drop => {
+                self.action_history.push(Action::Drop);
+                self.coord_history.push(self.current_coord);
+                // Settled by the world's fumble check
+                ActionOutcome::DropRefused
+            }
+        };
+        self.outcome_history.push(outcome);
+        self.use_energy(action);
         self.turn += 1;
     }
 
@@ -481,47 +895,47 @@ impl Robot {
         self.facing = direction;
     }
 
-    fn step(&mut self, grid: &mut Grid) {
-        match self.facing {
-            Direction::Left => {
-                let current_x = self.current_coord.x;
-                if current_x > 0 {
-                    grid.remove_robot(self, self.current_coord);
-                    self.current_coord.x -= 1;
-                    grid.add_robot(self, self.current_coord);
-                }
-            },
-            Direction::Right => {
-                let current_x = self.current_coord.x;
-                if current_x < grid.get_width() - 1 {
-                    grid.remove_robot(self, self.current_coord);
-                    self.current_coord.x += 1;
-                    grid.add_robot(self, self.current_coord);
-                }
-            },
-            Direction::Up => {
-                let current_y = self.current_coord.y;
-                if current_y < grid.get_height() - 1 {
-                    grid.remove_robot(self, self.current_coord);
-                    self.current_coord.y += 1;
-                    grid.add_robot(self, self.current_coord);
-                }
-            },
-            Direction::Down => {
-                let current_y = self.current_coord.y;
-                if current_y > 0 {
-                    grid.remove_robot(self, self.current_coord);
-                    self.current_coord.y -= 1;
-                    grid.add_robot(self, self.current_coord);
-                }
+    // Returns false when the robot is at the edge it faces
+    fn step(&mut self, grid: &mut Grid) -> bool {
+        match self.next_coord(grid.get_width(), grid.get_height()) {
+            Some(next_coord) => {
+                grid.remove_robot(self, self.current_coord);
+                self.current_coord = next_coord;
+                grid.add_robot(self, self.current_coord);
+                true
             },
+            None => false,
+        }
+    }
+
+    // Cell a move would take the robot to, None at the edge it faces
+    pub fn next_coord(&self, width: usize, height: usize) -> Option<Coord> {
+        let Coord { x, y } = self.current_coord;
+        match self.facing {
+            Direction::Left if x > 0 => Some(Coord::new(x - 1, y)),
+            Direction::Right if x < width - 1 => Some(Coord::new(x + 1, y)),
+            Direction::Up if y < height - 1 => Some(Coord::new(x, y + 1)),
+            Direction::Down if y > 0 => Some(Coord::new(x, y - 1)),
+            _ => None,
         }
     }
+
+    // A move the world turned back because the cell ahead was full
+    pub fn bounce(&mut self) {
+        self.action_history.push(Action::Move);
+        self.coord_history.push(self.current_coord);
+        self.outcome_history.push(ActionOutcome::Bounced);
+        self.message_board.lock().unwrap().get_metrics_mut().bounced_moves += 1;
+        self.use_energy(&Action::Move);
+        self.turn += 1;
+    }
     
-    pub fn pickup(&mut self, pair_id: char) {
+    pub fn pickup(&mut self, carrying_group: Vec<char>, value: u8) {
         if !self.is_carrying {
             self.is_carrying = true;
-            self.pair_id = Some(pair_id);
+            self.carrying_group = carrying_group;
+            self.carried_value = value;
+            self.refused_boxes.clear();
         }
     }
 
@@ -529,6 +943,17 @@ impl Robot {
         self.action_history.last().unwrap().clone()
     }
 
+    // Overrides this turn's outcome once the world has settled the action
+    pub fn report_outcome(&mut self, outcome: ActionOutcome) {
+        if let Some(latest) = self.outcome_history.last_mut() {
+            *latest = outcome;
+        }
+    }
+
+    pub fn get_latest_outcome(&self) -> Option<ActionOutcome> {
+        self.outcome_history.last().copied()
+    }
+
 }
 
 // Gold logic 
@@ -538,9 +963,33 @@ impl Robot {
             Team::Red => println!("{}{} has {} a {} at {:?}", "|".red(), self.id.to_string().red().bold(), "DROPPED".on_red().bold().italic(), "GOLD BAR".yellow().bold(), self.coord_history[self.turn - 1]),
             Team::Blue => println!("{}{} has {} a {} at {:?}", "|".blue(), self.id.to_string().blue().bold(), "DROPPED".on_red().bold().italic(), "GOLD BAR".yellow().bold(), self.coord_history[self.turn - 1]),
         }
-        self.is_carrying = false;
-        self.was_carrying = false;
-        self.coord_history[self.turn - 1]
+        let drop_coord = self.coord_history[self.turn - 1];
+        self.release_gold();
+        self.report_outcome(ActionOutcome::Fumbled(drop_coord));
+        drop_coord
+    }
+
+    pub fn put_down_gold(&mut self, low_battery: bool) -> Coord {
+        println!("{}{} has {} a {} at {:?}", self.team.style("|".to_string()), self.team.style(self.id.to_string()).bold(), "PUT DOWN".on_yellow().bold().italic(), "GOLD BAR".yellow().bold(), self.current_coord);
+        self.release_gold();
+        if low_battery {
+            self.report_outcome(ActionOutcome::PutDownLowBattery(self.current_coord));
+        } else {
+            self.report_outcome(ActionOutcome::Dropped(self.current_coord));
+        }
+        self.current_coord
+    }
+
+    // Without the bar the task is over, so the robot leaves its group and joins the next round
+    fn release_gold(&mut self) {
+        self.carried_value = 0;
+        self.tackled = false;
+        self.received_begin = true;
+        self.receiver_ids = self.local_cluster.clone();
+        self.scored();
+        self.local_cluster.clear();
+        self.reset();
+        self.planned_actions.clear();
     }
 
     pub fn score_gold(&mut self) {
@@ -548,6 +997,7 @@ impl Robot {
             Team::Red => println!("{}{} has {}", "|".red(), self.id.to_string().red().bold(), "SCORED!".green().bold()),
             Team::Blue => println!("{}{} has {}", "|".blue(), self.id.to_string().blue().bold(), "SCORED!".green().bold()),
         }
+        self.report_outcome(ActionOutcome::Scored(self.carried_value));
         self.is_carrying = false;
         self.was_carrying = false;
     }
@@ -558,45 +1008,65 @@ impl Robot {
 
     pub fn observe(&mut self, grid: &mut Grid) {
         // let mut target = self.current_coord;
+        let mut kb_delta: Vec<Observation> = Vec::new();
+        // Knowledge is stamped with the board turn so it compares with what teammates and enemies report
+        let now = self.message_board.lock().unwrap().get_current_turn();
         for observable_cell in self.observable_cells.iter() {
             let observed_cell = grid.get_cell(*observable_cell).unwrap();
             if observed_cell.get_gold_amount().is_some() && !self.send_target {
-                if observed_cell.get_gold_amount().unwrap() > self.max_gold_seen && !self.override_target_gold {
+                let priority = self.gold_priority(observed_cell.get_gold_value().unwrap(), Some(&observed_cell));
+                if priority > self.max_priority_seen && !self.override_target_gold {
                     if self.old_target_gold.is_some() {
                         if observed_cell.coord != self.old_target_gold.unwrap() {
-                            self.max_gold_seen = observed_cell.get_gold_amount().unwrap();
+                            self.max_priority_seen = priority;
                             self.target_gold = Some(observed_cell.coord);
-                            self.target_gold_amount = observed_cell.get_gold_amount().unwrap();
+                            self.target_gold_amount = observed_cell.get_gold_value().unwrap();
                             self.message_to_send = Some(Message::new(
                                 self.id,
                                 MessageType::Simple,
                                 self.id as u32,
-                                MessageContent::Coord(Some(observed_cell.coord), Some(observed_cell.get_gold_amount().unwrap())),
+                                MessageContent::Gold(observed_cell.coord, observed_cell.get_gold_amount().unwrap(), observed_cell.get_gold_value().unwrap()),
                             ));
                         }
                     } else {
-                        self.max_gold_seen = observed_cell.get_gold_amount().unwrap();
+                        self.max_priority_seen = priority;
                         self.target_gold = Some(observed_cell.coord);
-                        self.target_gold_amount = observed_cell.get_gold_amount().unwrap();
+                        self.target_gold_amount = observed_cell.get_gold_value().unwrap();
                         self.message_to_send = Some(Message::new(
                             self.id,
                             MessageType::Simple,
                             self.id as u32,
-                            MessageContent::Coord(Some(observed_cell.coord), Some(observed_cell.get_gold_amount().unwrap())),
+                            MessageContent::Gold(observed_cell.coord, observed_cell.get_gold_amount().unwrap(), observed_cell.get_gold_value().unwrap()),
                         ));
 
                     }
                 }
             }
             // self.knowledge_base.entry(observed_cell.coord).or_insert(observed_cell);
+            if self.kb_sync && observed_cell.is_deposit_box().is_none() && Self::is_changed(self.knowledge_base.get(&observed_cell.coord), &observed_cell) {
+                kb_delta.push((
+                    observed_cell.coord,
+                    observed_cell.get_gold_amount().zip(observed_cell.get_gold_value()),
+                    observed_cell.red_robots,
+                    observed_cell.blue_robots,
+                ));
+            }
+            self.knowledge_turns.insert(observed_cell.coord, now);
+            self.self_observed.insert(observed_cell.coord);
             self.knowledge_base.insert(observed_cell.coord, observed_cell);
 
         }
+        self.share_knowledge(kb_delta, now);
+        if (self.kb_sync || self.eavesdropping) && !self.send_target && self.target_gold.is_none() {
+            self.target_shared_gold();
+        }
         if !self.send_target {
             if self.target_gold.is_none() {
             } else {
-                self.send(self.message_to_send.unwrap(), self.receiver_ids.clone());
+                let report = self.byzantine_report(self.message_to_send.unwrap());
+                self.send(report, self.receiver_ids.clone());
                 self.send_target = true;
+                self.waiting_since = self.turn;
             }
         }
 
@@ -605,7 +1075,8 @@ impl Robot {
             if self.current_coord == self.target_gold.unwrap() {
                 self.current_state = RobotState::AtTarget;
                 if !self.received_direction && !self.sent_direction_request {
-                    if self.pre_pickup_pair_id.is_some() {
+                    // The group's highest id picks the direction, the others take it from the request
+                    if !self.pre_pickup_group.is_empty() && self.group_id == Some(self.id as u32) {
                         let propose_direction;
                         match rand::random_range(1..5) {
                             1 => propose_direction = Direction::Right,
@@ -620,7 +1091,7 @@ impl Robot {
                             self.id as u32,
                             MessageContent::TurnReq(propose_direction, self.target_gold.unwrap()),
                             // MessageContent::Direction(propose_direction),
-                        ), vec![self.pre_pickup_pair_id.unwrap()]);
+                        ), self.pre_pickup_group.clone());
                         self.sent_direction_request = true;
 
                     }
@@ -630,14 +1101,13 @@ impl Robot {
                 }
 
                 // If see other robots at target gold, send GetOut
-                if self.combined_pair_id.is_some() {
+                if self.group_id.is_some() {
                     match self.team {
                         Team::Blue => {
-                            if  self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().blue_robots > 2 {
-                                let (a, b) = self.consensus_pair.unwrap();
+                            if  self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().blue_robots as usize > self.consensus_group.len() {
                                 let filtered: Vec<char> = self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().blue_robots_ids
                                   .iter()
-                                  .filter(|&&c| c != a && c != b) // keep only chars that are not 'b' or 'd'
+                                  .filter(|c| !self.consensus_group.contains(c)) // keep only robots outside our group
                                   .cloned() // since iter() gives &char, we clone to get Vec<char>
                                   .collect();
                                 if !self.send_getout {
@@ -645,7 +1115,7 @@ impl Robot {
                                     self.send(Message::new(
                                         self.id,
                                         MessageType::GetOut,
-                                        self.combined_pair_id.unwrap(),
+                                        self.group_id.unwrap(),
                                         MessageContent::Coord(self.target_gold, Some(0u8)),
                                     ), filtered);
 
@@ -654,11 +1124,10 @@ impl Robot {
                             }
                         },
                         Team::Red => {
-                            if  self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().red_robots > 2 {
-                                let (a, b) = self.consensus_pair.unwrap();
+                            if  self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().red_robots as usize > self.consensus_group.len() {
                                 let filtered: Vec<char> = self.knowledge_base.get(&self.target_gold.unwrap()).unwrap().red_robots_ids
                                   .iter()
-                                  .filter(|&&c| c != a && c != b) // keep only chars that are not 'b' or 'd'
+                                  .filter(|c| !self.consensus_group.contains(c)) // keep only robots outside our group
                                   .cloned() // since iter() gives &char, we clone to get Vec<char>
                                   .collect();
                                 if !self.send_getout {
@@ -666,7 +1135,7 @@ impl Robot {
                                     self.send(Message::new(
                                         self.id,
                                         MessageType::GetOut,
-                                        self.combined_pair_id.unwrap(),
+                                        self.group_id.unwrap(),
                                         MessageContent::Coord(self.target_gold, Some(0u8)),
                                     ), filtered);
                                 }
@@ -682,7 +1151,7 @@ impl Robot {
         }
 
         // Gold gone before reaching/picking
-        if self.pre_pickup_pair_id.is_some() {
+        if !self.pre_pickup_group.is_empty() {
             if self.knowledge_base.get(&self.target_gold.unwrap()).is_some() {
                 let current_target_cell = self.knowledge_base.get(&self.target_gold.unwrap()).unwrap();
                 if current_target_cell.get_gold_amount().is_none() && self.is_second_check && !self.is_carrying {
@@ -833,63 +1302,278 @@ impl Robot {
     }
 }
 
+// Knowledge Sharing
+impl Robot {
+    fn is_changed(known_cell: Option<&Cell>, observed_cell: &Cell) -> bool {
+        match known_cell {
+            Some(known_cell) => {
+                known_cell.get_gold_amount() != observed_cell.get_gold_amount() ||
+                    known_cell.get_gold_value() != observed_cell.get_gold_value() ||
+                    known_cell.red_robots != observed_cell.red_robots ||
+                    known_cell.blue_robots != observed_cell.blue_robots
+            },
+            None => true
+        }
+    }
+
+    // The whole delta goes out as one message
+    fn share_knowledge(&mut self, kb_delta: Vec<Observation>, turn: u32) {
+        if kb_delta.is_empty() {
+            return;
+        }
+        let mut observations = [None; MAX_OBSERVATIONS];
+        for (slot, observation) in observations.iter_mut().zip(kb_delta.into_iter().take(self.kb_sync_max_cells)) {
+            *slot = Some(observation);
+        }
+        self.send(Message::new(
+            self.id,
+            MessageType::KnowledgeSync,
+            self.id as u32,
+            MessageContent::Observations(observations, turn),
+        ), self.teammate_ids.clone());
+        self.kb_sync_sent += 1;
+    }
+
+    // Freshest observation wins, ties are kept as our own observation
+    fn merge_observation(&mut self, observations: MessageContent) {
+        if let MessageContent::Observations(observations, turn) = observations {
+            for (coord, gold, red_robots, blue_robots) in observations.into_iter().flatten() {
+                let is_fresher = match self.knowledge_turns.get(&coord) {
+                    Some(known_turn) => turn > *known_turn,
+                    None => true,
+                };
+                if is_fresher {
+                    self.knowledge_base.insert(coord, Cell::from_observation(coord, gold, red_robots, blue_robots));
+                    self.knowledge_turns.insert(coord, turn);
+                    self.kb_sync_merged += 1;
+                }
+            }
+        }
+    }
+
+    // Value each member of the group would earn, less a penalty for every enemy last seen on the pile.
+    // Gold we have no cell for yet is assumed to need a pair and be free of enemies
+    fn gold_priority(&self, gold_value: u16, cell: Option<&Cell>) -> f64 {
+        let robots_required = cell.map(|cell| cell.get_robots_required(self.robots_required)).filter(|robots_required| *robots_required > 0).unwrap_or(2);
+        let enemies = cell.map(|cell| match self.team {
+            Team::Red => cell.blue_robots,
+            Team::Blue => cell.red_robots,
+        }).unwrap_or(0);
+        gold_value as f64 / robots_required as f64 - self.enemy_penalty * enemies as f64
+    }
+
+    // Reports are ranked against what we know of the cell, or against the report itself if we have not seen it
+    fn reported_priority(&self, coord: Coord, bars: u8, gold_value: u16) -> f64 {
+        match self.knowledge_base.get(&coord) {
+            Some(cell) => self.gold_priority(gold_value, Some(cell)),
+            None => self.gold_priority(gold_value, Some(&Cell::from_observation(coord, Some((bars, gold_value)), 0, 0))),
+        }
+    }
+
+    // Group size the bar at this cell needs, going by what teammates report when we have not seen it
+    fn robots_required_at(&self, coord: Coord) -> usize {
+        let reported = self.clusters.keys()
+            .find(|(reported, _, _)| *reported == coord)
+            .map(|&(_, bars, gold_value)| Cell::from_observation(coord, Some((bars, gold_value)), 0, 0));
+        self.knowledge_base.get(&coord).or(reported.as_ref())
+            .map(|cell| cell.get_robots_required(self.robots_required))
+            .filter(|robots_required| *robots_required > 0)
+            .unwrap_or(2)
+    }
+
+    fn target_shared_gold(&mut self) {
+        let mut best: Option<(Coord, u8, u16, f64)> = None;
+        for (coord, cell) in self.knowledge_base.iter() {
+            if Some(*coord) == self.old_target_gold || self.deposit_boxes.contains(coord) {
+                continue;
+            }
+            if let (Some(bars), Some(gold_value)) = (cell.get_gold_amount(), cell.get_gold_value()) {
+                let priority = self.gold_priority(gold_value, Some(cell));
+                best = match best {
+                    Some((best_coord, _, _, best_priority)) if best_priority > priority || (best_priority == priority && best_coord.priority(*coord)) => best,
+                    _ => Some((*coord, bars, gold_value, priority)),
+                };
+            }
+        }
+        if let Some((coord, bars, gold_value, priority)) = best {
+            self.max_priority_seen = priority;
+            self.target_gold = Some(coord);
+            self.target_gold_amount = gold_value;
+            self.message_to_send = Some(Message::new(
+                self.id,
+                MessageType::Simple,
+                self.id as u32,
+                MessageContent::Gold(coord, bars, gold_value),
+            ));
+        }
+    }
+}
+
+// Cross-team
+impl Robot {
+    // Enemy gold reports tell us where their targets are
+    pub fn overhear(&mut self, message: Message) {
+        self.message_board.lock().unwrap().get_metrics_mut().overheard += 1;
+        if let (MessageType::Simple, MessageContent::Gold(coord, bars, gold_value)) = (message.msg_type, message.message_content) && !self.knowledge_base.contains_key(&coord) {
+            println!("Robot {} overheard enemy gold at {:?} ({} bars worth {})", self.team.style(self.id.to_string()), coord, bars, gold_value);
+            self.knowledge_base.insert(coord, Cell::from_observation(coord, Some((bars, gold_value)), 0, 0));
+            self.knowledge_turns.insert(coord, message.sent_turn);
+        }
+    }
+
+    fn sees_enemy_carrier(&self) -> bool {
+        self.observable_cells.iter().any(|coord| match self.knowledge_base.get(coord) {
+            Some(cell) => self.tackle_config.in_range(self.current_coord, *coord) && cell.carrying_ids.iter().any(|id| match self.team {
+                Team::Red => cell.blue_robots_ids.contains(id),
+                Team::Blue => cell.red_robots_ids.contains(id),
+            }),
+            None => false,
+        })
+    }
+
+    fn sees_enemy(&self) -> bool {
+        self.observable_cells.iter().any(|coord| match self.knowledge_base.get(coord) {
+            Some(cell) => match self.team {
+                Team::Red => cell.blue_robots > 0,
+                Team::Blue => cell.red_robots > 0,
+            },
+            None => false,
+        })
+    }
+}
+
 // Conversation Logic
 impl Robot {
-    fn send(&mut self, message: Message, receiver_ids: Vec<char>) {
+    fn send(&mut self, mut message: Message, receiver_ids: Vec<char>) {
+        if self.energy_config.enabled && self.energy_config.message_cost > 0 {
+            if self.battery < self.energy_config.message_cost {
+                println!("Robot {} has no energy left to send {:?}", self.team.style(self.id.to_string()), message);
+                self.message_board.lock().unwrap().get_metrics_mut().unpowered_sends += 1;
+                return;
+            }
+            self.battery -= self.energy_config.message_cost;
+        }
+        message.epoch = self.epoch;
+        message.ttl = self.message_ttl;
+        if let Some(auth_key) = self.auth_key {
+            message.mac = auth_key.sign(&message);
+        }
         let mut message_board_guard = self.message_board.lock().unwrap();
+        message_board_guard.broadcast(message);
         for receiver_id in receiver_ids {
-            let mut random_timer_message = message;
-            let mut rng = rand::rng();
-            let timer = rng.random_range(0..=3);
-            random_timer_message.timer = timer;
-            message_board_guard.get_message_board().entry(receiver_id).or_default().send_messages(random_timer_message);
+            message_board_guard.send(receiver_id, message);
         }
     }
 
-    fn receive(&self) -> Option<Message> {
+    fn receive(&self) -> Vec<Message> {
         let mut message_board_guard = self.message_board.lock().unwrap();
-        let mut message_to_return = None;
-        if let Some(message_box) = message_board_guard.get_message_board().get_mut(&self.id) {
-            message_to_return = message_box.retrieve_messages()
-        }
+        let current_turn = message_board_guard.get_current_turn();
+        let mut messages = message_board_guard.retrieve(self.id);
+        messages.retain(|message| {
+            if self.auth_key.is_some_and(|auth_key| !auth_key.verify(message)) {
+                println!("Robot {} rejected unauthenticated {:?}", self.team.style(self.id.to_string()), message);
+                message_board_guard.get_metrics_mut().rejected_unauthenticated += 1;
+                false
+            } else if message.is_expired(current_turn) {
+                println!("Robot {} discarded expired {:?}", self.team.style(self.id.to_string()), message);
+                message_board_guard.get_metrics_mut().expired_discarded += 1;
+                false
+            } else if message.msg_type.is_round_scoped() && message.epoch < self.epoch {
+                println!("Robot {} discarded stale {:?} from epoch {} (current epoch {})", self.team.style(self.id.to_string()), message, message.epoch, self.epoch);
+                message_board_guard.get_metrics_mut().stale_discarded += 1;
+                false
+            } else {
+                true
+            }
+        });
         if self.logger_config.robot_message {
-            match message_to_return {
-                Some(message) => {
-                    println!("Robot {} received {:?}", self.team.style(self.id.to_string()), message);
-                },
-                None => {
-                    println!("Robot {} received None", self.team.style(self.id.to_string()));
-                }
+            if messages.is_empty() {
+                println!("Robot {} received None", self.team.style(self.id.to_string()));
+            }
+            for message in messages.iter() {
+                println!("Robot {} received {:?}", self.team.style(self.id.to_string()), message);
             }
         }
-        message_to_return
+        messages
     }
 
-    fn set_consensus(&mut self, consensus: MessageContent) {
-        match consensus {
+    fn consensus_context(&self) -> ConsensusContext {
+        ConsensusContext {
+            id: self.id,
+            team: self.team,
+            cluster: self.local_cluster.clone(),
+            turn: self.turn,
+            current_turn: self.message_board.lock().unwrap().get_current_turn(),
+        }
+    }
+
+    // Settle a decision before sending, so the Confirm never overtakes it
+    fn apply_consensus(&mut self, outgoing: Vec<Outgoing>) {
+        if let Some(decided) = self.consensus.take_decision() {
+            self.set_consensus(decided);
+        }
+        for (message, receiver_ids) in outgoing {
+            self.send(message, receiver_ids);
+        }
+    }
+
+    // Our cluster and those of the teammates we heard from, less us, are too few for their bar
+    fn cluster_too_small(&self) -> bool {
+        self.target_gold.is_none_or(|target| self.local_cluster.len() + 1 < self.robots_required_at(target))
+    }
+
+    // Robots whose clusters are too small for their bar join together at the best of those piles
+    fn merge_small_clusters(&mut self) {
+        let mut stragglers = Vec::new();
+        let mut best: Option<(Coord, u16, f64)> = self.target_gold.map(|coord| (coord, self.target_gold_amount, self.gold_priority(self.target_gold_amount, self.knowledge_base.get(&coord))));
+
+        for (&(coord, bars, gold_value), v) in &self.clusters {
+            let cluster_size = v.len() + usize::from(Some(coord) == self.target_gold);
+            if cluster_size < self.robots_required_at(coord) {
+                stragglers.extend(v.iter().copied());
+                let priority = self.reported_priority(coord, bars, gold_value);
+                best = match best {
+                    Some((best_coord, _, best_priority)) if best_priority > priority || (best_priority == priority && !coord.priority(best_coord)) => best,
+                    _ => Some((coord, gold_value, priority)),
+                };
+            }
+        }
+
+        self.local_cluster = stragglers;
+        if let Some((coord, gold_value, _)) = best {
+            self.target_gold = Some(coord);
+            self.target_gold_amount = gold_value;
+        }
+        // self.consensus_coord = max_coord;
+        self.current_state = RobotState::Paxos;
+    }
+
+    fn set_consensus(&mut self, decided: Message) {
+        let mut message_board_guard = self.message_board.lock().unwrap();
+        message_board_guard.report_decision(self.id, self.epoch, self.local_cluster.clone(), decided);
+        let with_byzantine = self.byzantine_in_decision(decided);
+        let fake_report = self.decided_on_fake_report(decided);
+        message_board_guard.get_metrics_mut().record_decision((self.turn - self.consensus_started_turn) as u32, with_byzantine, fake_report);
+        drop(message_board_guard);
+        match decided.message_content {
             MessageContent::Coord(Some(coord), _) => {
                 self.consensus_coord = Some(coord);
                 println!("Robot {} has Consensus coord: {:?}", self.team.style(self.id.to_string()), self.consensus_coord);
             },
-            MessageContent::Pair(a, b) => {
-                self.consensus_pair = Some((a, b));
+            MessageContent::Group(_) => {
+                self.consensus_group = decided.message_content.members();
                 self.received_begin = false;
                 self.consensus_coord = self.target_gold;
-                println!("Robot {} has Consensus pair: {:?}", self.team.style(self.id.to_string()), self.consensus_pair);
-                // Self is chosen as designated pair
-                // if (self.id == a || self.id == b) && self.planned_actions.is_empty() && self.target_gold.is_some() {
-                if (self.id == a || self.id == b) && self.target_gold.is_some() {
+                println!("Robot {} has Consensus group: {:?}", self.team.style(self.id.to_string()), self.consensus_group);
+                let chosen = self.consensus_group.contains(&self.id);
+                if chosen && self.is_byzantine(ByzantineBehaviour::RefusePickup) {
+                    println!("Robot {} {} to pick up", self.team.style(self.id.to_string()).bold(), "REFUSES".red().bold());
+                    self.message_board.lock().unwrap().get_metrics_mut().refused_pickups += 1;
+                    self.current_state = RobotState::WaitingForTaskCompletion;
+                } else if chosen && self.target_gold.is_some() {
                     self.planned_actions.clear();
-                    if a as u32 > b as u32 {
-                        self.combined_pair_id = Some(a as u32);
-                    } else {
-                        self.combined_pair_id = Some(b as u32);
-                    }
-                    if self.id == a {
-                        self.pre_pickup_pair_id = Some(b);
-                    } else {
-                        self.pre_pickup_pair_id = Some(a);
-                    }
+                    self.group_id = self.consensus_group.iter().max().map(|id| *id as u32);
+                    self.pre_pickup_group = self.consensus_group.iter().copied().filter(|id| *id != self.id).collect();
                     self.plan_actions_to_move_to(self.target_gold.unwrap());
                     println!("Plan to move to {:?}: {:?}", self.target_gold.unwrap(), self.planned_actions);
                     self.current_state = RobotState::MovingToTarget;
@@ -905,153 +1589,13 @@ impl Robot {
         match received_message {
             Some(message) => {
                 match message.msg_type {
-                    MessageType::PrepareRequest => {
-                        if self.current_state == RobotState::Paxos {
-                            match self.promised_message {
-                                Some(promised_message) => {
-                                    if promised_message.id < message.id {
-                                        println!("Robot {} Piggybacked", self.team.style(self.id.to_string()));
-                                        self.promised_message = Some(Message::new(
-                                            promised_message.sender_id,
-                                            promised_message.msg_type,
-                                            message.id,
-                                            promised_message.message_content,
-                                        ));
-                                        println!("{:?}", self.promised_message);
-                                        let piggyback_msg = Message::new(
-                                            self.id,
-                                            MessageType::PrepareResponse,
-                                            promised_message.id,
-                                            promised_message.message_content,
-                                        );
-                                        self.send(piggyback_msg, vec![message.sender_id]);
-                                    } else {
-                                        // let nack_msg = Message::new(
-                                        //     self.id,
-                                        //     MessageType::Nack,
-                                        //     promised_message.id,
-                                        //     promised_message.coord,
-                                        // );
-                                        // self.send(nack_msg, vec![message.sender_id]);
-                                    }
-                                },
-                                None => {
-                                    self.promised_message = Some(message);
-                                    let promised = Message::new(
-                                        self.id,
-                                        MessageType::PrepareResponse,
-                                        message.id,
-                                        message.message_content,
-                                    );
-                                    self.send(promised, vec![message.sender_id]);
-                                }
-                            }
-                        }
-                    },
-                    MessageType::AcceptRequest => {
-                        if self.current_state == RobotState::Paxos {
-                            match self.promised_message {
-                                Some(promised_message) => {
-                                    println!("Promised Message: {:?}", promised_message);
-                                    println!("Received Message: {:?}", message);
-                                    if promised_message.id <= message.id && !self.accepted {
-                                        self.accepted = true;
-                                        // self.set_consensus(message.message_content);
-                                        self.promised_message = Some(message);
-                                        let accepted_msg = Message::new(
-                                            self.id,
-                                            MessageType::Accepted,
-                                            message.id,
-                                            message.message_content,
-                                        );
-                                        self.send(accepted_msg, vec![message.sender_id]);
-                                    } else {
-                                        // let nack_msg = Message::new(
-                                        //     self.id,
-                                        //     MessageType::Nack,
-                                        //     promised_message.id,
-                                        //     promised_message.coord,
-                                        // );
-                                        // self.send(nack_msg, vec![message.sender_id]);
-                                    }
-                                },
-                                None => {}
-                            }
-                        }
-                    },
-                    MessageType::PrepareResponse => {
-                        if self.current_state == RobotState::Paxos {
-                            self.promise_count += 1;
-                            if message.id == self.message_to_send.unwrap().id && !self.piggybacked {
-                                if self.promise_count > self.majority && !self.reached_majority {
-                                    self.reached_majority = true;
-                                    println!("Robot {} has received majority promises", self.team.style(self.id.to_string()));
-                                    let message_to_send = self.message_to_send.unwrap();
-                                    let accept_request_msg = Message::new(
-                                        self.id,
-                                        MessageType::AcceptRequest,
-                                        message_to_send.id,
-                                        message_to_send.message_content,
-                                    );
-                                    self.send(accept_request_msg, self.local_cluster.clone());
-                                }
-                            } else {
-                                self.piggybacked = true;
-                                // Update highset piggyback ID
-                                if message.id > self.max_piggyback_id_seen {
-                                    self.max_piggyback_id_seen = message.id;
-                                    let message_to_send = self.message_to_send.unwrap();
-                                    let new_message_to_send = Message::new(
-                                        self.id,
-                                        MessageType::AcceptRequest,
-                                        message_to_send.id,
-                                        message.message_content,
-                                    );
-                                    self.message_to_send = Some(new_message_to_send);
-                                }
-                                // Check majority
-                                if self.promise_count > self.majority && !self.reached_majority {
-                                    self.reached_majority = true;
-                                    println!("Robot {} has received majority promises", self.team.style(self.id.to_string()));
-                                    self.send(self.message_to_send.unwrap(), self.local_cluster.clone());
-                                }
-                            }
-                        }
-                    },
-                    MessageType::Accepted => {
-                        if self.current_state == RobotState::Paxos {
-                            self.accept_count += 1;
-                            if self.accept_count > self.majority {
-                                self.set_consensus(message.message_content);
-                                self.promised_message = Some(message);
-                                self.send(Message::new(
-                                    self.id,
-                                    MessageType::Confirm,
-                                    self.id as u32,
-                                    message.message_content,
-                                ), self.local_cluster.clone());
-                                // self.current_state = RobotState::MovingToTarget;
-                            }
-                        }
-                    },
-                    MessageType::Confirm => {
-                        if self.current_state == RobotState::Paxos {
-                            self.set_consensus(message.message_content);
-                            // self.current_state = RobotState::MovingToTarget;
-                        }
-                    }
-                    MessageType::Nack => {
-                        if self.current_state == RobotState::Paxos {
-                            self.max_id_seen = message.id;
-                            let Message { message_content, .. } = self.message_to_send.unwrap();
-                            let new_message_to_send = Message::new(
-                                self.id,
-                                MessageType::PrepareRequest,
-                                self.max_id_seen + self.increment,
-                                message_content,
-                            );
-                            self.message_to_send = Some(new_message_to_send);
-                            self.send(new_message_to_send, self.local_cluster.clone());
+                    MessageType::PrepareRequest | MessageType::AcceptRequest | MessageType::PrepareResponse | MessageType::Accepted | MessageType::Confirm | MessageType::Nack => {
+                        if self.current_state == RobotState::Paxos && self.is_byzantine(ByzantineBehaviour::ConflictingVotes) && (message.msg_type == MessageType::PrepareRequest || message.msg_type == MessageType::AcceptRequest) {
+                            self.vote_for_everyone(message);
+                        } else if self.current_state == RobotState::Paxos {
+                            let context = self.consensus_context();
+                            let outgoing = self.consensus.receive(&context, message);
+                            self.apply_consensus(outgoing);
                         }
                     },
                     MessageType::Simple => {
@@ -1061,6 +1605,10 @@ impl Robot {
                                 self.local_cluster.clear();
                                 self.reset();
                             }
+                            // Align with teammates that already started a later round
+                            if self.current_state == RobotState::ClusterFinding {
+                                self.epoch = self.epoch.max(message.epoch);
+                            }
                             if self.not_received_simple > 0 {
                                 self.not_received_simple -= 1;
                                 if self.not_received_simple == 0 {
@@ -1068,15 +1616,16 @@ impl Robot {
                                 }
                                 if self.target_gold.is_some() {
                                     match message.message_content {
-                                        MessageContent::Coord(Some(coord), Some(gold_amount)) => {
-                                            let list = self.clusters.entry((coord, gold_amount)).or_insert(vec![]);
+                                        MessageContent::Gold(coord, bars, gold_value) => {
+                                            let list = self.clusters.entry((coord, bars, gold_value)).or_insert(vec![]);
                                             list.push(message.sender_id);
                                             if coord == self.target_gold.unwrap() {
                                                 self.local_cluster.push(message.sender_id);
                                             }
-                                            if gold_amount > self.max_gold_receive {
+                                            let priority = self.reported_priority(coord, bars, gold_value);
+                                            if priority > self.max_priority_receive {
                                                 self.backup_cluster.clear();
-                                                self.max_gold_receive = gold_amount;
+                                                self.max_priority_receive = priority;
                                                 self.max_gold_receive_coord = Some(coord);
                                             }
                                             match self.max_gold_receive_coord {
@@ -1092,25 +1641,26 @@ impl Robot {
                                     }
                                 } else {
                                     match message.message_content {
-                                        MessageContent::Coord(Some(coord), Some(gold_amount)) => {
+                                        MessageContent::Gold(coord, bars, gold_value) => {
                                             self.override_target_gold = true;
                                             self.target_gold = Some(coord);
-                                            self.max_gold_seen = gold_amount;
-                                            self.target_gold_amount = gold_amount;
+                                            self.max_priority_seen = self.reported_priority(coord, bars, gold_value);
+                                            self.target_gold_amount = gold_value;
                                             self.message_to_send = Some(Message::new(
                                                 self.id,
                                                 MessageType::Simple,
                                                 self.id as u32,
-                                                MessageContent::Coord(Some(coord), Some(gold_amount)),
+                                                MessageContent::Gold(coord, bars, gold_value),
                                             ));
-                                            let list = self.clusters.entry((coord, gold_amount)).or_insert(vec![]);
+                                            let list = self.clusters.entry((coord, bars, gold_value)).or_insert(vec![]);
                                             list.push(message.sender_id);
                                             if coord == self.target_gold.unwrap() {
                                                 self.local_cluster.push(message.sender_id);
                                             }
-                                            if gold_amount > self.max_gold_receive {
+                                            let priority = self.reported_priority(coord, bars, gold_value);
+                                            if priority > self.max_priority_receive {
                                                 self.backup_cluster.clear();
-                                                self.max_gold_receive = gold_amount;
+                                                self.max_priority_receive = priority;
                                                 self.max_gold_receive_coord = Some(coord);
                                             }
                                             match self.max_gold_receive_coord {
@@ -1125,49 +1675,8 @@ impl Robot {
                                         _ => {}
                                     }
                                 }
-                                if self.not_received_simple == 0 && self.local_cluster.is_empty() {
-                                    let mut singles = Vec::new();
-                                    let mut max_key: Option<u8> = Some(self.target_gold_amount);
-                                    let mut max_coord: Option<Coord> = self.target_gold;
-
-                                    for (&(coord, gold_amount), v) in &self.clusters {
-                                        if v.len() == 1 {
-                                            singles.push(v[0]);
-
-                                            // track max key
-                                            match max_key {
-                                                Some(current) => {
-                                                    if gold_amount == current {
-                                                        match max_coord {
-                                                            Some(current_coord) => {
-                                                                if coord.priority(current_coord) {
-                                                                    max_coord = Some(coord);
-                                                                    max_key = Some(current);
-                                                                }
-                                                            },
-                                                            _ => {}
-                                                        }
-                                                    } else if gold_amount > current {
-                                                        max_coord = Some(coord);
-                                                        max_key = Some(current);
-                                                    }
-                                                },
-                                                _ => {
-                                                    max_coord = Some(coord);
-                                                    max_key = Some(gold_amount);
-                                                }
-                                            }
-                                            max_key = Some(match max_key {
-                                                Some(current) if current > gold_amount => current,
-                                                _ => gold_amount,
-                                            });
-                                        }
-                                    }
-
-                                    self.local_cluster = singles;
-                                    self.target_gold = max_coord;
-                                    // self.consensus_coord = max_coord;
-                                    self.current_state = RobotState::Paxos;
+                                if self.not_received_simple == 0 && self.cluster_too_small() {
+                                    self.merge_small_clusters();
                                 }
                             }
                     },
@@ -1176,7 +1685,7 @@ impl Robot {
                             match message.message_content {
                                 MessageContent::TurnReq(direction, coord) => {
                                     if coord == self.target_gold.unwrap() {
-                                        if self.id < message.sender_id || self.current_coord != self.target_gold.unwrap() {
+                                        if self.group_id == Some(message.sender_id as u32) {
                                             self.send(Message::new(
                                                 self.id,
                                                 MessageType::Ack,
@@ -1199,7 +1708,7 @@ impl Robot {
                             match message.message_content {
                                 MessageContent::TurnReq(direction, coord) => {
                                     if coord == self.target_gold.unwrap() {
-                                        if self.current_coord == self.target_gold.unwrap() {
+                                        if self.current_coord == self.target_gold.unwrap() && !self.turned {
                                             self.planned_actions.push(Turn(direction));
                                             self.turned = true;
                                         }
@@ -1215,14 +1724,18 @@ impl Robot {
                             self.receiver_ids = self.local_cluster.clone();
                             self.local_cluster.clear();
                             self.reset();
+                            self.epoch = self.epoch.max(message.epoch);
                         }
                     },
+                    MessageType::KnowledgeSync => {
+                        self.merge_observation(message.message_content);
+                    },
                     MessageType::GetOut => {
-                        match self.combined_pair_id {
-                            Some(combined_pair_id) => {
+                        match self.group_id {
+                            Some(group_id) => {
                                 match message.message_content {
                                     MessageContent::Coord(Some(get_out_coord), _) => {
-                                        if self.current_coord == get_out_coord && message.id > combined_pair_id && self.current_coord == self.target_gold.unwrap() && (!self.is_carrying || self.carrying_with_wrong_pair) {
+                                        if self.current_coord == get_out_coord && message.id > group_id && self.current_coord == self.target_gold.unwrap() && (!self.is_carrying || self.carrying_with_wrong_group) {
                                             self.old_target_gold = self.target_gold;
                                             self.received_begin = true;
                                             self.receiver_ids = self.local_cluster.clone();
@@ -1230,7 +1743,7 @@ impl Robot {
                                             self.local_cluster.clear();
                                             self.reset();
                                             self.planned_actions.clear();
-                                            self.plan_actions_to_move_to(self.deposit_box_coord);
+                                            self.plan_actions_to_move_to(self.nearest_deposit_box());
                                             // self.planned_actions.push(Action::Turn(Direction::Left));
                                             // self.planned_actions.push(Action::Move);
                                             // self.planned_actions.push(Action::Turn(Direction::Up));
@@ -1238,12 +1751,12 @@ impl Robot {
                                             // self.planned_actions.push(Action::Turn(Direction::Right));
                                             // self.planned_actions.push(Action::Move);
                                         } else if self.planned_actions.is_empty() && self.current_state != RobotState::AtTarget {
-                                            self.plan_actions_to_move_to(self.deposit_box_coord);
+                                            self.plan_actions_to_move_to(self.nearest_deposit_box());
                                         }
                                     },
                                     _ => {
                                         if self.planned_actions.is_empty() {
-                                            self.plan_actions_to_move_to(self.deposit_box_coord);
+                                            self.plan_actions_to_move_to(self.nearest_deposit_box());
                                         }
                                     }
                                 }
@@ -1260,8 +1773,121 @@ impl Robot {
 }
 
 
+// Byzantine behaviour
+impl Robot {
+    fn is_byzantine(&self, behaviour: ByzantineBehaviour) -> bool {
+        self.byzantine.contains(&behaviour)
+    }
+
+    pub fn is_adversarial(&self) -> bool {
+        !self.byzantine.is_empty()
+    }
+
+    // Whether a Byzantine robot decided, was part of the cluster or was chosen for the pair, looked up in the fault config
+    fn byzantine_in_decision(&self, decided: Message) -> bool {
+        let fault_config = FaultConfig::new();
+        let mut participants = self.local_cluster.clone();
+        participants.push(self.id);
+        participants.extend(decided.message_content.members());
+        participants.iter().any(|id| !fault_config.byzantine_behaviours(*id).is_empty())
+    }
+
+    // Whether the decided gold was never seen by this robot and only reported by teammates lying about gold
+    fn decided_on_fake_report(&self, decided: Message) -> bool {
+        let coord = match decided.message_content {
+            MessageContent::Coord(Some(coord), _) => Some(coord),
+            MessageContent::Group(_) => self.target_gold,
+            _ => None,
+        };
+        let Some(coord) = coord else {
+            return false;
+        };
+        if self.knowledge_base.get(&coord).is_some_and(|cell| cell.get_gold_amount().is_some()) {
+            return false;
+        }
+        let fault_config = FaultConfig::new();
+        let mut reporters = self.clusters.iter()
+            .filter(|((reported, _, _), _)| *reported == coord)
+            .flat_map(|(_, senders)| senders.iter())
+            .peekable();
+        reporters.peek().is_some() && reporters.all(|id| fault_config.byzantine_behaviours(*id).contains(&ByzantineBehaviour::FakeGold))
+    }
+
+    fn record_adversarial_message(&self) {
+        self.message_board.lock().unwrap().get_metrics_mut().adversarial_messages += 1;
+    }
+
+    // Swaps an honest gold report for a made up one
+    fn byzantine_report(&self, report: Message) -> Message {
+        if !self.is_byzantine(ByzantineBehaviour::FakeGold) {
+            return report;
+        }
+        let Config { width, height, max_gold, max_bar_value, .. } = Config::new();
+        let fake_coord = Coord::random(0..width, 0..height);
+        let fake_bars = rand::random_range(1..=max_gold);
+        let fake_value = rand::random_range(fake_bars as u16..=fake_bars as u16 * max_bar_value as u16);
+        println!("Robot {} lies about gold at {:?} ({} bars worth {})", self.team.style(self.id.to_string()).bold(), fake_coord, fake_bars, fake_value);
+        self.record_adversarial_message();
+        Message::new(
+            report.sender_id,
+            report.msg_type,
+            report.id,
+            MessageContent::Gold(fake_coord, fake_bars, fake_value),
+        )
+    }
+
+    // Answers every prepare and accept positively, whatever was promised before
+    fn vote_for_everyone(&mut self, message: Message) {
+        let msg_type = match message.msg_type {
+            MessageType::PrepareRequest => MessageType::PrepareResponse,
+            _ => MessageType::Accepted,
+        };
+        self.record_adversarial_message();
+        self.send(Message::new(
+            self.id,
+            msg_type,
+            message.id,
+            message.message_content,
+        ).with_ballot(message.id), vec![message.sender_id]);
+    }
+
+    // Tells everyone at a known gold pile to leave it, with an id no pair can outrank
+    fn spam_getout(&mut self) {
+        let gold_coords: Vec<Coord> = self.knowledge_base.values()
+            .filter(|cell| cell.get_gold_amount().is_some())
+            .map(|cell| cell.coord)
+            .collect();
+        let Some(&coord) = gold_coords.choose(&mut rand::rng()) else {
+            return;
+        };
+        self.record_adversarial_message();
+        self.send(Message::new(
+            self.id,
+            MessageType::GetOut,
+            u32::MAX,
+            MessageContent::Coord(Some(coord), Some(0u8)),
+        ), self.teammate_ids.clone());
+    }
+}
+
 // Move Planning
 impl Robot {
+    // Nearest of the team's boxes that is open and has not turned the carried bar away, ties go to the lower
+    // coordinate. Members of a group see different things of the boxes, so only the team's turn and what the
+    // group went through decide, which keeps a group standing on one cell heading for the same box.
+    fn nearest_deposit_box(&self) -> Coord {
+        let turn = self.message_board.lock().unwrap().get_current_turn();
+        let usable = |coord: &&Coord| self.deposit_rules[*coord].is_open(turn) && !self.refused_boxes.contains(coord);
+        let candidates: Vec<&Coord> = if self.deposit_boxes.iter().any(|coord| usable(&coord)) {
+            self.deposit_boxes.iter().filter(usable).collect()
+        } else {
+            self.deposit_boxes.iter().collect()
+        };
+        **candidates.iter()
+            .min_by_key(|coord| (self.current_coord.x.abs_diff(coord.x) + self.current_coord.y.abs_diff(coord.y), coord.x, coord.y))
+            .unwrap()
+    }
+
     pub fn plan_actions_to_move_to(&mut self, target: Coord) {
         let current = self.current_coord;
         let travel_x = target.x as i32 - current.x as i32;
@@ -1326,14 +1952,14 @@ impl Debug for Robot {
             Team::Red => {
                 write!(f, "{}({:?}) is at {:?} facing {:?} - ", self.id.to_string().red(), self.current_state, self.current_coord, self.facing)?;
                 write!(f, "Consensus coord: {:?} - ", self.consensus_coord)?;
-                write!(f, "Consensus pair: {:?} - ", self.consensus_pair)?;
+                write!(f, "Consensus group: {:?} - ", self.consensus_group)?;
                 write!(f, "Target gold: {:?} - ", self.target_gold)?;
                 write!(f, "Local cluster: {:?}", self.local_cluster)?;
                 if self.target_gold.is_some() {
                     write!(f, " Current target gold observation: {:?}", self.knowledge_base.get(&self.target_gold.unwrap()))?;
                 }
                 if self.is_carrying {
-                    write!(f, " is {} with {}", "CARRYING GOLD".yellow().bold(), self.pair_id.unwrap().to_string().red().dimmed())
+                    write!(f, " is {} with {}", "CARRYING GOLD".yellow().bold(), format!("{:?}", self.carrying_group).red().dimmed())
                 } else {
                     write!(f, "")
                 }
@@ -1341,14 +1967,14 @@ impl Debug for Robot {
             Team::Blue => {
                 write!(f, "{}({:?}) is at {:?} facing {:?} - ", self.id.to_string().blue(), self.current_state, self.current_coord, self.facing)?;
                 write!(f, "Consensus coord: {:?} - ", self.consensus_coord)?;
-                write!(f, "Consensus pair: {:?} - ", self.consensus_pair)?;
+                write!(f, "Consensus group: {:?} - ", self.consensus_group)?;
                 write!(f, "Target gold: {:?} - ", self.target_gold)?;
                 write!(f, "Local cluster: {:?}", self.local_cluster)?;
                 if self.target_gold.is_some() {
                     write!(f, " Current target gold observation: {:?}", self.knowledge_base.get(&self.target_gold.unwrap()))?;
                 }
                 if self.is_carrying {
-                    write!(f, " is {} with {}", "CARRYING GOLD".yellow().bold(), self.pair_id.unwrap().to_string().blue().dimmed())
+                    write!(f, " is {} with {}", "CARRYING GOLD".yellow().bold(), format!("{:?}", self.carrying_group).blue().dimmed())
                 } else {
                     write!(f, "")
                 }